@@ -0,0 +1,832 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! An owning counterpart to `FixedVec`, backed by an array.
+
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops;
+use core::ptr;
+use core::slice::{self, SliceIndex};
+
+use super::{uninit_array, CapacityError, FixedVec, Iter, IterMut, Result};
+
+/// A fixed-capacity vector that owns its backing memory.
+///
+/// Unlike `FixedVec`, a `FixedArrayVec` does not borrow its memory, so it can
+/// be returned from functions, stored in structs without a lifetime, or
+/// placed in a `static`. It offers the same methods as `FixedVec`, and can
+/// lend itself out as a `FixedVec` with `as_fixed_vec`.
+///
+/// # Example
+///
+/// ```
+/// use fixedvec::FixedArrayVec;
+///
+/// struct Driver {
+///     pending: FixedArrayVec<u8, 16>,
+/// }
+///
+/// let mut driver = Driver { pending: FixedArrayVec::new() };
+/// driver.pending.push_all(&[1, 2, 3]).unwrap();
+/// assert_eq!(driver.pending.as_slice(), &[1, 2, 3]);
+/// ```
+pub struct FixedArrayVec<T, const N: usize> {
+    memory: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> FixedArrayVec<T, N> {
+    /// Create a new, empty `FixedArrayVec`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fixedvec::FixedArrayVec;
+    ///
+    /// static EMPTY: FixedArrayVec<u32, 8> = FixedArrayVec::new();
+    /// assert_eq!(EMPTY.capacity(), 8);
+    /// assert_eq!(EMPTY.len(), 0);
+    /// ```
+    pub const fn new() -> Self {
+        FixedArrayVec {
+            memory: uninit_array(),
+            len: 0,
+        }
+    }
+
+    /// Returns a `FixedVec` that manages this vector's memory and contents.
+    ///
+    /// Changes made through the returned `FixedVec` are reflected in this
+    /// vector once it goes out of scope.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fixedvec::{FixedArrayVec, FixedVec};
+    ///
+    /// fn fill(vec: &mut FixedVec<u8>) {
+    ///     vec.push_all(&[1, 2, 3]).unwrap();
+    /// }
+    ///
+    /// let mut vec: FixedArrayVec<u8, 4> = FixedArrayVec::new();
+    /// fill(&mut vec.as_fixed_vec());
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn as_fixed_vec(&mut self) -> FixedVecView<'_, T> {
+        let memory: &mut [MaybeUninit<T>] = &mut self.memory;
+        FixedVecView {
+            origin: memory as *const [MaybeUninit<T>],
+            vec: ManuallyDrop::new(FixedVec {
+                memory,
+                len: self.len,
+            }),
+            len: &mut self.len,
+        }
+    }
+
+    /// Returns the capacity of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// assert_eq!(vec.capacity(), 16);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of elements in the vector. This will always be
+    /// less than or equal to the `capacity()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push(1).unwrap();
+    /// vec.push(2).unwrap();
+    /// assert_eq!(vec.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of available elements in the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push(1).unwrap();
+    /// assert_eq!(vec.available(), 15);
+    /// ```
+    #[inline]
+    pub fn available(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// assert!(vec.is_empty());
+    /// vec.push(1).unwrap();
+    /// assert!(!vec.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Extracts a slice containing the entire vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.memory.as_ptr() as *const T, self.len) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push(1).unwrap();
+    /// vec.as_mut_slice()[0] = 2;
+    /// assert_eq!(vec.as_slice(), &[2]);
+    /// ```
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.memory.as_mut_ptr() as *mut T, self.len) }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less than or equal to `capacity()`, and the elements
+    /// at `old_len..new_len` must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= N);
+        self.len = new_len;
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it one position to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the vector's length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 4> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// vec.insert(1, 15).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 15, 2, 3]);
+    /// assert!(vec.insert(2, 17).is_err());
+    /// ```
+    pub fn insert(
+        &mut self,
+        index: usize,
+        element: T,
+    ) -> core::result::Result<(), CapacityError<T>> {
+        self.as_fixed_vec().insert(index, element)
+    }
+
+    /// Inserts an element at position `index` within the vector, returning an
+    /// error rather than panicking if `index` is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 4> = FixedArrayVec::new();
+    /// assert!(vec.try_insert(1, 15).is_err());
+    /// vec.try_insert(0, 15).unwrap();
+    /// assert_eq!(vec.as_slice(), &[15]);
+    /// ```
+    pub fn try_insert(
+        &mut self,
+        index: usize,
+        element: T,
+    ) -> core::result::Result<(), CapacityError<T>> {
+        self.as_fixed_vec().try_insert(index, element)
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it one position to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec.remove(1), 2);
+    /// assert_eq!(vec.as_slice(), &[1, 3]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        self.as_fixed_vec().remove(index)
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// returning an error rather than panicking if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec.try_remove(1), Ok(2));
+    /// assert!(vec.try_remove(2).is_err());
+    /// ```
+    pub fn try_remove(&mut self, index: usize) -> Result<T> {
+        self.as_fixed_vec().try_remove(index)
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 2> = FixedArrayVec::new();
+    /// vec.push(1).unwrap();
+    /// vec.push(2).unwrap();
+    /// assert!(vec.push(3).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, value: T) -> core::result::Result<(), CapacityError<T>> {
+        self.as_fixed_vec().push(value)
+    }
+
+    /// Removes the last element from the vector and returns it, or `None` if
+    /// the vector is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2]).unwrap();
+    /// assert_eq!(vec.pop(), Some(2));
+    /// assert_eq!(vec.pop(), Some(1));
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.as_fixed_vec().pop()
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.as_fixed_vec().clear()
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// vec.truncate(1);
+    /// assert_eq!(vec.as_slice(), &[1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        self.as_fixed_vec().truncate(len)
+    }
+
+    /// Applies the function `f` to all elements in the vector, mutating the
+    /// vector in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// vec.map_in_place(|x: &mut u8| *x *= 2);
+    /// assert_eq!(vec.as_slice(), &[2, 4, 6]);
+    /// ```
+    pub fn map_in_place<F>(&mut self, f: F)
+    where
+        F: Fn(&mut T),
+    {
+        self.as_fixed_vec().map_in_place(f)
+    }
+
+    /// Provides a forward iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2]).unwrap();
+    /// let mut iter = vec.iter();
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Provides a mutable forward iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// *vec.iter_mut().next().unwrap() = 5;
+    /// assert_eq!(vec.as_slice(), &[5, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Removes an element from anywhere in the vector and returns it,
+    /// replacing it with the last element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[0, 1, 2, 3]).unwrap();
+    /// assert_eq!(vec.swap_remove(1), 1);
+    /// assert_eq!(vec.as_slice(), &[0, 3, 2]);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        self.as_fixed_vec().swap_remove(index)
+    }
+
+    /// Removes an element from anywhere in the vector and returns it,
+    /// replacing it with the last element. Returns an error rather than
+    /// panicking if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[0, 1, 2, 3]).unwrap();
+    /// assert_eq!(vec.try_swap_remove(1), Ok(1));
+    /// assert!(vec.try_swap_remove(3).is_err());
+    /// ```
+    pub fn try_swap_remove(&mut self, index: usize) -> Result<T> {
+        self.as_fixed_vec().try_swap_remove(index)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// vec.retain(|&x| x % 2 == 0);
+    /// assert_eq!(vec.as_slice(), &[2, 4]);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.as_fixed_vec().retain(f)
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if the
+    /// index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[10, 40, 30]).unwrap();
+    /// assert_eq!(Some(&40), vec.get(1));
+    /// assert_eq!(None, vec.get(3));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    /// Returns a mutable reference to the element at the given index, or
+    /// `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 16> = FixedArrayVec::new();
+    /// vec.push_all(&[10, 40, 30]).unwrap();
+    /// *vec.get_mut(1).unwrap() = 50;
+    /// assert_eq!(Some(&50), vec.get(1));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+}
+
+impl<T, const N: usize> FixedArrayVec<T, N>
+where
+    T: Clone,
+{
+    /// Clones all elements from slice `other` to this vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 5> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// assert!(vec.push_all(&[5, 6, 7]).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn push_all(&mut self, other: &[T]) -> Result<()> {
+        self.as_fixed_vec().push_all(other)
+    }
+
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 10> = FixedArrayVec::new();
+    /// vec.resize(3, 255);
+    /// assert_eq!(vec.as_slice(), &[255, 255, 255]);
+    /// vec.resize(1, 0);
+    /// assert_eq!(vec.as_slice(), &[255]);
+    /// ```
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        self.as_fixed_vec().resize(new_len, value)
+    }
+
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`,
+    /// returning an error rather than panicking if `new_len` is greater than
+    /// capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 4> = FixedArrayVec::new();
+    /// vec.try_resize(2, 255).unwrap();
+    /// assert!(vec.try_resize(5, 0).is_err());
+    /// assert_eq!(vec.as_slice(), &[255, 255]);
+    /// ```
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<()> {
+        self.as_fixed_vec().try_resize(new_len, value)
+    }
+}
+
+impl<T, const N: usize> FixedArrayVec<T, N>
+where
+    T: PartialEq<T>,
+{
+    /// Removes consecutive repeated elements in the vector in O(N) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedArrayVec;
+    /// let mut vec: FixedArrayVec<u8, 10> = FixedArrayVec::new();
+    /// vec.push_all(&[1, 2, 2, 3, 2]).unwrap();
+    /// vec.dedup();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 2]);
+    /// ```
+    pub fn dedup(&mut self) {
+        self.as_fixed_vec().dedup()
+    }
+}
+
+impl<T, const N: usize> Drop for FixedArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for FixedArrayVec<T, N> {
+    fn default() -> Self {
+        FixedArrayVec::new()
+    }
+}
+
+impl<T, const N: usize> Clone for FixedArrayVec<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut vec = FixedArrayVec::new();
+        vec.push_all(self.as_slice()).unwrap();
+        vec
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for FixedArrayVec<T, N>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a FixedArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut FixedArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> Hash for FixedArrayVec<T, N>
+where
+    T: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(self.as_slice(), state)
+    }
+}
+
+impl<T, const N: usize> Extend<T> for FixedArrayVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        self.as_fixed_vec().extend(iterable)
+    }
+}
+
+impl<T, const N: usize> ops::Deref for FixedArrayVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> ops::DerefMut for FixedArrayVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for FixedArrayVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for FixedArrayVec<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for FixedArrayVec<T, N> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> BorrowMut<[T]> for FixedArrayVec<T, N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, I, const N: usize> ops::Index<I> for FixedArrayVec<T, N>
+where
+    I: SliceIndex<[T]>,
+{
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &I::Output {
+        ops::Index::index(self.as_slice(), index)
+    }
+}
+
+impl<T, I, const N: usize> ops::IndexMut<I> for FixedArrayVec<T, N>
+where
+    I: SliceIndex<[T]>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        ops::IndexMut::index_mut(self.as_mut_slice(), index)
+    }
+}
+
+impl_slice_eq! { [A, B, const N: usize, const M: usize] FixedArrayVec<A, N>, FixedArrayVec<B, M> }
+impl_slice_eq! { ['a, A, B, const N: usize] FixedArrayVec<A, N>, FixedVec<'a, B> }
+impl_slice_eq! { ['a, A, B, const N: usize] FixedVec<'a, A>, FixedArrayVec<B, N> }
+impl_slice_eq! { [A, B, const N: usize] FixedArrayVec<A, N>, [B] }
+impl_slice_eq! { ['b, A, B, const N: usize] FixedArrayVec<A, N>, &'b [B] }
+impl_slice_eq! { ['b, A, B, const N: usize] FixedArrayVec<A, N>, &'b mut [B] }
+impl_slice_eq! { [A, B, const N: usize, const M: usize] FixedArrayVec<A, N>, [B; M] }
+impl_slice_eq! { ['b, A, B, const N: usize, const M: usize] FixedArrayVec<A, N>, &'b [B; M] }
+impl_slice_eq! { [A, B, const N: usize] [A], FixedArrayVec<B, N> }
+impl_slice_eq! { ['b, A, B, const N: usize] &'b [A], FixedArrayVec<B, N> }
+impl_slice_eq! { ['b, A, B, const N: usize] &'b mut [A], FixedArrayVec<B, N> }
+impl_slice_eq! { [A, B, const N: usize, const M: usize] [A; M], FixedArrayVec<B, N> }
+#[cfg(feature = "alloc")]
+impl_slice_eq! { [A, B, const N: usize] FixedArrayVec<A, N>, alloc::vec::Vec<B> }
+#[cfg(feature = "alloc")]
+impl_slice_eq! { [A, B, const N: usize] alloc::vec::Vec<A>, FixedArrayVec<B, N> }
+
+impl<T, const N: usize> Eq for FixedArrayVec<T, N> where T: Eq {}
+
+impl<T, const N: usize> PartialOrd for FixedArrayVec<T, N>
+where
+    T: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &FixedArrayVec<T, N>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(self.as_slice(), other.as_slice())
+    }
+}
+
+impl<T, const N: usize> Ord for FixedArrayVec<T, N>
+where
+    T: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &FixedArrayVec<T, N>) -> Ordering {
+        Ord::cmp(self.as_slice(), other.as_slice())
+    }
+}
+
+/// A `FixedVec` borrowing the memory of a `FixedArrayVec`.
+///
+/// Dereferences to `FixedVec`. When the view is dropped, the length of the
+/// borrowed `FixedArrayVec` is updated to match. Created with
+/// `FixedArrayVec::as_fixed_vec`.
+pub struct FixedVecView<'a, T: 'a> {
+    origin: *const [MaybeUninit<T>],
+    vec: ManuallyDrop<FixedVec<'a, T>>,
+    len: &'a mut usize,
+}
+
+impl<'a, T> ops::Deref for FixedVecView<'a, T> {
+    type Target = FixedVec<'a, T>;
+
+    #[inline]
+    fn deref(&self) -> &FixedVec<'a, T> {
+        &self.vec
+    }
+}
+
+impl<'a, T> ops::DerefMut for FixedVecView<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut FixedVec<'a, T> {
+        &mut self.vec
+    }
+}
+
+impl<'a, T> Drop for FixedVecView<'a, T> {
+    fn drop(&mut self) {
+        if ptr::eq(&*self.vec.memory, self.origin) {
+            // The elements now belong to the array again.
+            *self.len = self.vec.len;
+        } else {
+            // The `FixedVec` was swapped out for one managing other memory.
+            // Whoever holds the original is now responsible for our elements,
+            // and the replacement is dropped like any other `FixedVec`.
+            *self.len = 0;
+            unsafe { ManuallyDrop::drop(&mut self.vec) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedArrayVec;
+    use std::mem;
+    use std::prelude::v1::*;
+    use FixedVec;
+
+    #[test]
+    fn test_non_copy() {
+        let mut vec: FixedArrayVec<String, 3> = FixedArrayVec::new();
+        vec.push(String::from("a")).unwrap();
+        vec.push(String::from("c")).unwrap();
+        vec.insert(1, String::from("b")).unwrap();
+        assert_eq!(vec.as_slice(), &["a", "b", "c"]);
+        assert!(vec.push(String::from("d")).is_err());
+
+        let copy = vec.clone();
+        assert_eq!(vec, copy);
+    }
+
+    #[test]
+    fn test_view_swapped_out() {
+        let mut vec: FixedArrayVec<String, 4> = FixedArrayVec::new();
+        vec.push(String::from("a")).unwrap();
+
+        let mut space = alloc_stack_uninit!([String; 2]);
+        {
+            let mut other = FixedVec::from_uninit(&mut space);
+            other.push(String::from("b")).unwrap();
+            {
+                let mut view = vec.as_fixed_vec();
+                mem::swap(&mut *view, &mut other);
+            }
+            assert_eq!(other.as_slice(), &["a"]);
+        }
+        // The array's element went with the swapped-out `FixedVec`
+        assert!(vec.is_empty());
+    }
+}
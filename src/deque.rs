@@ -0,0 +1,887 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A double-ended queue with fixed capacity, stored in a ring buffer.
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::MaybeUninit;
+use core::ops::{self, Range};
+use core::ptr;
+use core::slice;
+
+use super::{as_uninit_mut, CapacityError};
+
+/// A double-ended queue that stores its elements in preallocated memory.
+///
+/// The memory is used as a ring buffer, so elements can be added and removed
+/// at either end in O(1) time, without shifting the others. This makes
+/// `FixedDeque` a better fit for FIFO queues than `FixedVec`, where removing
+/// the first element moves every other element.
+///
+/// Because the contents can wrap around the end of the memory, they are not
+/// always stored in one contiguous slice. `as_slices` returns them in two
+/// parts, and `make_contiguous` rearranges them into one.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedDeque;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 4]);
+/// let mut queue = FixedDeque::new(&mut space);
+///
+/// queue.push_back(1).unwrap();
+/// queue.push_back(2).unwrap();
+/// queue.push_front(0).unwrap();
+/// assert_eq!(queue.pop_front(), Some(0));
+/// assert_eq!(queue.pop_back(), Some(2));
+/// assert_eq!(queue.len(), 1);
+/// # }
+/// ```
+pub struct FixedDeque<'a, T: 'a> {
+    memory: &'a mut [MaybeUninit<T>],
+    head: usize,
+    len: usize,
+}
+
+impl<'a, T> FixedDeque<'a, T>
+where
+    T: 'a + Copy,
+{
+    /// Create a new, empty `FixedDeque` from the provided slice, in the
+    /// process taking ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way, since the slice still holds
+    /// valid values once the `FixedDeque` is gone. Use `from_uninit` for
+    /// other types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let queue = FixedDeque::new(&mut space);
+    /// assert_eq!(queue.capacity(), 16);
+    /// assert_eq!(queue.len(), 0);
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [T]) -> Self {
+        FixedDeque::from_uninit(as_uninit_mut(memory))
+    }
+}
+
+impl<'a, T> FixedDeque<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new, empty `FixedDeque` from the provided slice of
+    /// uninitialized memory, in the process taking ownership of the slice.
+    ///
+    /// This works for any element type, including types that are not `Copy`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([String; 4]);
+    /// let mut queue = FixedDeque::from_uninit(&mut space);
+    /// queue.push_back(String::from("hello")).unwrap();
+    /// assert_eq!(queue[0], "hello");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedDeque {
+            memory,
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let queue = FixedDeque::new(&mut space);
+    /// assert_eq!(queue.capacity(), 16);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Returns the number of elements in the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_front(2).unwrap();
+    /// assert_eq!(queue.len(), 2);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of elements that can be added before the queue is
+    /// full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// assert_eq!(queue.available(), 15);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if no more elements can be added to the queue.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.available() == 0
+    }
+
+    /// Maps a logical index, which must be less than the capacity, to a
+    /// position in memory.
+    #[inline]
+    fn wrap(&self, index: usize) -> usize {
+        let pos = self.head + index;
+        if pos >= self.capacity() {
+            pos - self.capacity()
+        } else {
+            pos
+        }
+    }
+
+    /// Returns the positions in memory of the front and back parts of the
+    /// contents.
+    #[inline]
+    fn ranges(&self) -> (Range<usize>, Range<usize>) {
+        let end = self.head + self.len;
+        if end <= self.capacity() {
+            (self.head..end, 0..0)
+        } else {
+            (self.head..self.capacity(), 0..end - self.capacity())
+        }
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// If the queue is full, the element is handed back inside the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_back(2).unwrap();
+    /// assert_eq!(queue.push_back(3).unwrap_err().into_inner(), 3);
+    /// # }
+    /// ```
+    pub fn push_back(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(value));
+        }
+        let pos = self.wrap(self.len);
+        self.memory[pos] = MaybeUninit::new(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Prepends an element to the front of the queue.
+    ///
+    /// If the queue is full, the element is handed back inside the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_front(1).unwrap();
+    /// queue.push_front(2).unwrap();
+    /// assert_eq!(queue.front(), Some(&2));
+    /// # }
+    /// ```
+    pub fn push_front(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(value));
+        }
+        self.head = self.wrap(self.capacity() - 1);
+        self.memory[self.head] = MaybeUninit::new(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Appends an element to the back of the queue, removing the element at
+    /// the front first if the queue is full.
+    ///
+    /// Returns the element that was removed to make room, if any. This keeps
+    /// the most recent elements, as is usually wanted for logs. If the
+    /// capacity is zero, `value` itself is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 3]);
+    /// let mut log = FixedDeque::new(&mut space);
+    /// for i in 1..=3 {
+    ///     assert_eq!(log.push_back_overwrite(i), None);
+    /// }
+    /// assert_eq!(log.push_back_overwrite(4), Some(1));
+    /// assert!(log.iter().eq(&[2, 3, 4]));
+    /// # }
+    /// ```
+    pub fn push_back_overwrite(&mut self, value: T) -> Option<T> {
+        let oldest = if self.is_full() {
+            self.pop_front()
+        } else {
+            None
+        };
+        match self.push_back(value) {
+            Ok(()) => oldest,
+            Err(err) => Some(err.into_inner()),
+        }
+    }
+
+    /// Removes the element at the front of the queue and returns it, or
+    /// `None` if the queue is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_back(2).unwrap();
+    /// assert_eq!(queue.pop_front(), Some(1));
+    /// assert_eq!(queue.pop_front(), Some(2));
+    /// assert_eq!(queue.pop_front(), None);
+    /// # }
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = unsafe { ptr::read(self.memory[self.head].as_ptr()) };
+        self.head = self.wrap(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes the element at the back of the queue and returns it, or
+    /// `None` if the queue is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_back(2).unwrap();
+    /// assert_eq!(queue.pop_back(), Some(2));
+    /// assert_eq!(queue.pop_back(), Some(1));
+    /// assert_eq!(queue.pop_back(), None);
+    /// # }
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let pos = self.wrap(self.len);
+        Some(unsafe { ptr::read(self.memory[pos].as_ptr()) })
+    }
+
+    /// Returns a reference to the element at `index`, counted from the
+    /// front, or `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// assert_eq!(queue.get(1), Some(&2));
+    /// assert_eq!(queue.get(2), None);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            let pos = self.wrap(index);
+            Some(unsafe { &*self.memory[pos].as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, counted from
+    /// the front, or `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// *queue.get_mut(0).unwrap() = 5;
+    /// assert_eq!(queue[0], 5);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let pos = self.wrap(index);
+            Some(unsafe { &mut *self.memory[pos].as_mut_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the element at the front, or `None` if the
+    /// queue is empty.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the element at the front, or `None` if
+    /// the queue is empty.
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the element at the back, or `None` if the
+    /// queue is empty.
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// Returns a mutable reference to the element at the back, or `None` if
+    /// the queue is empty.
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        match self.len.checked_sub(1) {
+            Some(i) => self.get_mut(i),
+            None => None,
+        }
+    }
+
+    /// Removes all elements from the queue, dropping them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.clear();
+    /// assert!(queue.is_empty());
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        let (front, back) = self.ranges();
+        // Empty the queue first, so that a panicking destructor cannot cause
+        // the same element to be dropped twice.
+        self.head = 0;
+        self.len = 0;
+        let base = self.memory.as_mut_ptr() as *mut T;
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                base.add(front.start),
+                front.len(),
+            ));
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                base.add(back.start),
+                back.len(),
+            ));
+        }
+    }
+
+    /// Returns the contents of the queue as a pair of slices, which together
+    /// hold all elements from front to back.
+    ///
+    /// The second slice is empty unless the contents wrap around the end of
+    /// the memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_back(3).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// assert_eq!(queue.as_slices(), (&[1][..], &[2, 3][..]));
+    /// # }
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back) = self.ranges();
+        let base = self.memory.as_ptr() as *const T;
+        unsafe {
+            (
+                slice::from_raw_parts(base.add(front.start), front.len()),
+                slice::from_raw_parts(base.add(back.start), back.len()),
+            )
+        }
+    }
+
+    /// Returns the contents of the queue as a pair of mutable slices, which
+    /// together hold all elements from front to back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// let (front, back) = queue.as_mut_slices();
+    /// front[0] = 10;
+    /// back[0] = 20;
+    /// assert_eq!(queue.as_slices(), (&[10][..], &[20][..]));
+    /// # }
+    /// ```
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back) = self.ranges();
+        let base = self.memory.as_mut_ptr() as *mut T;
+        // The two ranges never overlap.
+        unsafe {
+            (
+                slice::from_raw_parts_mut(base.add(front.start), front.len()),
+                slice::from_raw_parts_mut(base.add(back.start), back.len()),
+            )
+        }
+    }
+
+    /// Rearranges the contents of the queue so that they are stored in one
+    /// contiguous slice, and returns that slice.
+    ///
+    /// This takes O(capacity) time if the contents wrap around the end of
+    /// the memory, and O(1) otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_back(3).unwrap();
+    /// queue.push_front(1).unwrap();
+    ///
+    /// queue.make_contiguous().sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(queue.as_slices(), (&[3, 2, 1][..], &[][..]));
+    /// # }
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.head + self.len > self.capacity() {
+            self.memory.rotate_left(self.head);
+            self.head = 0;
+        }
+        self.as_mut_slices().0
+    }
+
+    /// Returns an iterator over the elements of the queue, from front to
+    /// back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// let mut iter = queue.iter();
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> DequeIter<'_, T> {
+        let (front, back) = self.as_slices();
+        DequeIter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// Returns an iterator over mutable references to the elements of the
+    /// queue, from front to back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// for x in queue.iter_mut() {
+    ///     *x *= 10;
+    /// }
+    /// assert!(queue.iter().eq(&[10, 20]));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> DequeIterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        DequeIterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+/// An iterator over the elements of a `FixedDeque`, created by
+/// `FixedDeque::iter`.
+pub struct DequeIter<'b, T: 'b> {
+    front: slice::Iter<'b, T>,
+    back: slice::Iter<'b, T>,
+}
+
+impl<'b, T> Iterator for DequeIter<'b, T> {
+    type Item = &'b T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'b T> {
+        match self.front.next() {
+            Some(x) => Some(x),
+            None => self.back.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'b, T> DoubleEndedIterator for DequeIter<'b, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'b T> {
+        match self.back.next_back() {
+            Some(x) => Some(x),
+            None => self.front.next_back(),
+        }
+    }
+}
+
+impl<'b, T> ExactSizeIterator for DequeIter<'b, T> {}
+
+impl<'b, T> Clone for DequeIter<'b, T> {
+    fn clone(&self) -> Self {
+        DequeIter {
+            front: self.front.clone(),
+            back: self.back.clone(),
+        }
+    }
+}
+
+/// An iterator over mutable references to the elements of a `FixedDeque`,
+/// created by `FixedDeque::iter_mut`.
+pub struct DequeIterMut<'b, T: 'b> {
+    front: slice::IterMut<'b, T>,
+    back: slice::IterMut<'b, T>,
+}
+
+impl<'b, T> Iterator for DequeIterMut<'b, T> {
+    type Item = &'b mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'b mut T> {
+        match self.front.next() {
+            Some(x) => Some(x),
+            None => self.back.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'b, T> DoubleEndedIterator for DequeIterMut<'b, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'b mut T> {
+        match self.back.next_back() {
+            Some(x) => Some(x),
+            None => self.front.next_back(),
+        }
+    }
+}
+
+impl<'b, T> ExactSizeIterator for DequeIterMut<'b, T> {}
+
+impl<'a, T> Drop for FixedDeque<'a, T> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<'a, T> fmt::Debug for FixedDeque<'a, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b FixedDeque<'a, T> {
+    type Item = &'b T;
+    type IntoIter = DequeIter<'b, T>;
+
+    fn into_iter(self) -> DequeIter<'b, T> {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b mut FixedDeque<'a, T> {
+    type Item = &'b mut T;
+    type IntoIter = DequeIterMut<'b, T>;
+
+    fn into_iter(self) -> DequeIterMut<'b, T> {
+        self.iter_mut()
+    }
+}
+
+// Like `FixedVec`, stops once the queue is full.
+impl<'a, T> Extend<T> for FixedDeque<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        let mut iter = iterable.into_iter();
+        while !self.is_full() {
+            match iter.next() {
+                Some(value) => {
+                    let pos = self.wrap(self.len);
+                    self.memory[pos] = MaybeUninit::new(value);
+                    self.len += 1;
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl<'a, 'b, A, B> PartialEq<FixedDeque<'b, B>> for FixedDeque<'a, A>
+where
+    A: PartialEq<B>,
+{
+    fn eq(&self, other: &FixedDeque<'b, B>) -> bool {
+        self.len == other.len && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<'a, T> Eq for FixedDeque<'a, T> where T: Eq {}
+
+impl<'a, T> Hash for FixedDeque<'a, T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for element in self.iter() {
+            element.hash(state);
+        }
+    }
+}
+
+impl<'a, T> ops::Index<usize> for FixedDeque<'a, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(element) => element,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, index
+            ),
+        }
+    }
+}
+
+impl<'a, T> ops::IndexMut<usize> for FixedDeque<'a, T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len;
+        match self.get_mut(index) {
+            Some(element) => element,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedDeque;
+    use std::prelude::v1::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_wrap_around() {
+        let mut space = alloc_stack!([u8; 3]);
+        let mut queue = FixedDeque::new(&mut space);
+        for round in 0..10 {
+            queue.push_back(round).unwrap();
+            queue.push_back(round + 1).unwrap();
+            assert_eq!(queue.pop_front(), Some(round));
+            assert_eq!(queue.pop_front(), Some(round + 1));
+            assert!(queue.is_empty());
+        }
+
+        queue.push_back(1).unwrap();
+        queue.push_back(2).unwrap();
+        queue.push_front(0).unwrap();
+        assert!(queue.push_front(9).is_err());
+        assert!(queue.iter().eq(&[0, 1, 2]));
+        assert!(queue.iter().rev().eq(&[2, 1, 0]));
+        assert_eq!(queue.iter().len(), 3);
+        assert_eq!(queue.back(), Some(&2));
+    }
+
+    #[test]
+    fn test_make_contiguous_non_copy() {
+        let mut space = alloc_stack_uninit!([String; 4]);
+        let mut queue = FixedDeque::from_uninit(&mut space);
+        queue.push_back(String::from("c")).unwrap();
+        queue.push_front(String::from("b")).unwrap();
+        queue.push_front(String::from("a")).unwrap();
+        assert_eq!(queue.make_contiguous(), &["a", "b", "c"]);
+        assert_eq!(queue.as_slices().1.len(), 0);
+        assert_eq!(queue.pop_back().unwrap(), "c");
+    }
+
+    #[test]
+    fn test_overwrite_zero_capacity() {
+        let mut space: [u8; 0] = [];
+        let mut queue = FixedDeque::new(&mut space);
+        assert_eq!(queue.push_back_overwrite(1), Some(1));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_drop_elements() {
+        let counter = Rc::new(());
+        {
+            let mut space = alloc_stack_uninit!([Rc<()>; 3]);
+            let mut queue = FixedDeque::from_uninit(&mut space);
+            for _ in 0..5 {
+                queue.push_back_overwrite(counter.clone());
+            }
+            // Wrap the contents around the end of the memory
+            queue.pop_front();
+            queue.push_back(counter.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&counter), 4);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "the len is 1 but the index is 1")]
+    fn test_index_out_of_bounds() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut queue = FixedDeque::new(&mut space);
+        queue.push_back(1).unwrap();
+        let _ = queue[1];
+    }
+}
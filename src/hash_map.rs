@@ -0,0 +1,856 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A hash map with fixed capacity, over buckets provided by the caller.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::mem;
+use core::mem::MaybeUninit;
+use core::ops;
+use core::slice;
+
+use super::CapacityError;
+
+/// A hash map that stores its entries in preallocated buckets.
+///
+/// Entries are placed with open addressing and linear probing, and removals
+/// shift later entries back rather than leaving markers behind, so lookups
+/// stay fast however many entries have come and gone. Lookups, insertions
+/// and removals take O(1) time on average.
+///
+/// By default, every bucket can be filled. As the map fills up, the runs of
+/// occupied buckets that lookups have to search get longer; `set_max_len`
+/// limits the number of entries to keep lookups fast, at the cost of some
+/// unused buckets.
+///
+/// `core` has no hasher of its own, so the hasher type `S` needs to be named
+/// or passed to `with_hasher`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedHashMap;
+/// # fn main() {
+/// use std::collections::hash_map::RandomState;
+///
+/// let mut buckets = alloc_stack!([Option<(u32, &str)>; 16]);
+/// let mut sessions: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+///
+/// sessions.insert(1001, "alice").unwrap();
+/// sessions.insert(1002, "bob").unwrap();
+/// assert_eq!(sessions.get(&1001), Some(&"alice"));
+/// assert_eq!(sessions.remove(&1002), Some("bob"));
+/// assert_eq!(sessions.len(), 1);
+/// # }
+/// ```
+pub struct FixedHashMap<'a, K: 'a, V: 'a, S> {
+    buckets: &'a mut [Option<(K, V)>],
+    len: usize,
+    max_len: usize,
+    hash_builder: S,
+}
+
+impl<'a, K, V, S> FixedHashMap<'a, K, V, S>
+where
+    K: 'a + Hash + Eq,
+    V: 'a,
+    S: BuildHasher + Default,
+{
+    /// Create a new, empty `FixedHashMap` from the provided buckets, in the
+    /// process taking ownership of them.
+    ///
+    /// Any entries already in the buckets are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedHashMap;
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack!([Option<(u8, u8)>; 8]);
+    /// let map: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+    /// assert_eq!(map.capacity(), 8);
+    /// # }
+    /// ```
+    pub fn new(buckets: &'a mut [Option<(K, V)>]) -> Self {
+        FixedHashMap::with_hasher(buckets, S::default())
+    }
+
+    /// Create a new, empty `FixedHashMap` from the provided uninitialized
+    /// buckets, in the process taking ownership of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedHashMap;
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack_uninit!([Option<(String, u32)>; 8]);
+    /// let mut map: FixedHashMap<_, _, RandomState> = FixedHashMap::from_uninit(&mut buckets);
+    /// map.insert(String::from("alice"), 1001).unwrap();
+    /// assert_eq!(map["alice"], 1001);
+    /// # }
+    /// ```
+    pub fn from_uninit(buckets: &'a mut [MaybeUninit<Option<(K, V)>>]) -> Self {
+        FixedHashMap::from_uninit_with_hasher(buckets, S::default())
+    }
+}
+
+impl<'a, K, V, S> FixedHashMap<'a, K, V, S>
+where
+    K: 'a + Hash + Eq,
+    V: 'a,
+    S: BuildHasher,
+{
+    /// Create a new, empty `FixedHashMap` from the provided buckets, which
+    /// uses `hash_builder` to hash keys.
+    ///
+    /// Any entries already in the buckets are dropped.
+    pub fn with_hasher(buckets: &'a mut [Option<(K, V)>], hash_builder: S) -> Self {
+        for bucket in buckets.iter_mut() {
+            *bucket = None;
+        }
+        FixedHashMap {
+            max_len: buckets.len(),
+            buckets,
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    /// Create a new, empty `FixedHashMap` from the provided uninitialized
+    /// buckets, which uses `hash_builder` to hash keys.
+    pub fn from_uninit_with_hasher(
+        buckets: &'a mut [MaybeUninit<Option<(K, V)>>],
+        hash_builder: S,
+    ) -> Self {
+        for bucket in buckets.iter_mut() {
+            *bucket = MaybeUninit::new(None);
+        }
+        // Every bucket has just been initialized.
+        let buckets = unsafe {
+            &mut *(buckets as *mut [MaybeUninit<Option<(K, V)>>] as *mut [Option<(K, V)>])
+        };
+        FixedHashMap {
+            max_len: buckets.len(),
+            buckets,
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    /// Returns the number of entries the map can hold.
+    ///
+    /// This is the number of buckets, unless lowered with `set_max_len`.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns the number of buckets.
+    #[inline]
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Limits the number of entries the map can hold to `max_len`, or to the
+    /// number of buckets if that is lower.
+    ///
+    /// Entries already in the map are kept, even if there are more than
+    /// `max_len` of them, but no new ones can be added until enough have
+    /// been removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedHashMap;
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack!([Option<(u8, u8)>; 16]);
+    /// let mut map: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+    /// // Keep at least a quarter of the buckets free
+    /// map.set_max_len(12);
+    ///
+    /// for i in 0..12 {
+    ///     map.insert(i, i).unwrap();
+    /// }
+    /// assert!(map.insert(12, 12).is_err());
+    /// # }
+    /// ```
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = core::cmp::min(max_len, self.buckets.len());
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of entries that can be added before the map is
+    /// full.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.max_len.saturating_sub(self.len)
+    }
+
+    /// Returns `true` if the map contains no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the map's `BuildHasher`.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Returns the bucket that entries for `key` are placed in first.
+    ///
+    /// There must be at least one bucket.
+    // `BuildHasher::hash_one` is newer than the minimum supported Rust.
+    #[allow(clippy::manual_hash_one)]
+    fn ideal_bucket<Q>(&self, key: &Q) -> usize
+    where
+        Q: Hash + ?Sized,
+    {
+        let mut hasher = self.hash_builder.build_hasher();
+        key.hash(&mut hasher);
+        (hasher.finish() % self.buckets.len() as u64) as usize
+    }
+
+    #[inline]
+    fn next_bucket(&self, index: usize) -> usize {
+        if index + 1 == self.buckets.len() {
+            0
+        } else {
+            index + 1
+        }
+    }
+
+    /// Returns the bucket holding the entry for `key`, if there is one.
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let mut index = self.ideal_bucket(key);
+        for _ in 0..self.buckets.len() {
+            match self.buckets[index] {
+                Some((ref k, _)) if k.borrow() == key => return Some(index),
+                Some(_) => index = self.next_bucket(index),
+                None => return None,
+            }
+        }
+        None
+    }
+
+    /// Places an entry for a key that is not in the map, returning the bucket
+    /// it was placed in.
+    fn place(&mut self, key: K, value: V) -> Result<usize, CapacityError<(K, V)>> {
+        if self.available() == 0 {
+            return Err(CapacityError::new((key, value)));
+        }
+        // There are fewer entries than buckets, so there is an empty one.
+        let mut index = self.ideal_bucket(&key);
+        while self.buckets[index].is_some() {
+            index = self.next_bucket(index);
+        }
+        self.buckets[index] = Some((key, value));
+        self.len += 1;
+        Ok(index)
+    }
+
+    /// Empties the bucket at `index`, then moves later entries back into it
+    /// where that keeps them reachable from their ideal buckets.
+    fn take_bucket(&mut self, mut hole: usize) -> (K, V) {
+        let entry = self.buckets[hole].take().expect("bucket is occupied");
+        self.len -= 1;
+        let mut index = hole;
+        loop {
+            index = self.next_bucket(index);
+            let ideal = match self.buckets[index] {
+                Some((ref k, _)) => self.ideal_bucket(k),
+                None => break,
+            };
+            // The entry can only move back if its ideal bucket does not lie
+            // cyclically within (hole, index].
+            let stays = if hole <= index {
+                hole < ideal && ideal <= index
+            } else {
+                hole < ideal || ideal <= index
+            };
+            if !stays {
+                self.buckets[hole] = self.buckets[index].take();
+                hole = index;
+            }
+        }
+        entry
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the key was already present, its value is replaced and the old
+    /// value returned. If the map is full, the pair is handed back inside
+    /// the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedHashMap};
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack!([Option<(u8, u8)>; 1]);
+    /// let mut map: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+    /// assert_eq!(map.insert(1, 10).unwrap(), None);
+    /// assert_eq!(map.insert(1, 11).unwrap(), Some(10));
+    ///
+    /// let err = map.insert(2, 20).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::NoSpace);
+    /// assert_eq!(err.into_inner(), (2, 20));
+    /// # }
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CapacityError<(K, V)>> {
+        match self.find(&key) {
+            Some(index) => {
+                let entry = self.buckets[index].as_mut().expect("bucket is occupied");
+                Ok(Some(mem::replace(&mut entry.1, value)))
+            }
+            None => self.place(key, value).map(|_| None),
+        }
+    }
+
+    /// Removes a key from the map, returning its value if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedHashMap;
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack!([Option<(u8, u8)>; 4]);
+    /// let mut map: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+    /// map.insert(1, 10).unwrap();
+    /// assert_eq!(map.remove(&1), Some(10));
+    /// assert_eq!(map.remove(&1), None);
+    /// # }
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and its value if
+    /// it was present.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|index| self.take_bucket(index))
+    }
+
+    /// Returns a reference to the value for the key, if there is one.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key)
+            .and_then(|index| self.buckets[index].as_ref())
+            .map(|entry| &entry.1)
+    }
+
+    /// Returns a mutable reference to the value for the key, if there is
+    /// one.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.find(key) {
+            Some(index) => self.buckets[index].as_mut().map(|entry| &mut entry.1),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns the entry for the key, for in-place updates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedHashMap;
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack!([Option<(char, u32)>; 8]);
+    /// let mut counts: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+    /// for ch in "hello".chars() {
+    ///     *counts.entry(ch).or_insert(0).unwrap() += 1;
+    /// }
+    /// assert_eq!(counts.get(&'l'), Some(&2));
+    /// assert_eq!(counts.len(), 4);
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> HashMapEntry<'_, 'a, K, V, S> {
+        match self.find(&key) {
+            Some(index) => HashMapEntry::Occupied(HashMapOccupiedEntry { map: self, index }),
+            None => HashMapEntry::Vacant(HashMapVacantEntry { map: self, key }),
+        }
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(&mut self) {
+        // Empty the map first, in case a destructor panics.
+        self.len = 0;
+        for bucket in self.buckets.iter_mut() {
+            *bucket = None;
+        }
+    }
+
+    /// Returns an iterator over the entries of the map, in no particular
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedHashMap;
+    /// # fn main() {
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut buckets = alloc_stack!([Option<(u8, u8)>; 8]);
+    /// let mut map: FixedHashMap<_, _, RandomState> = FixedHashMap::new(&mut buckets);
+    /// map.insert(1, 10).unwrap();
+    /// map.insert(2, 20).unwrap();
+    /// let sum: u8 = map.iter().map(|(_, v)| v).sum();
+    /// assert_eq!(sum, 30);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> HashMapIter<'_, K, V> {
+        HashMapIter {
+            inner: self.buckets.iter(),
+            remaining: self.len,
+        }
+    }
+
+    /// Returns an iterator over the entries of the map, in no particular
+    /// order, with mutable references to the values.
+    #[inline]
+    pub fn iter_mut(&mut self) -> HashMapIterMut<'_, K, V> {
+        HashMapIterMut {
+            inner: self.buckets.iter_mut(),
+            remaining: self.len,
+        }
+    }
+}
+
+/// An entry in a `FixedHashMap`, created by `FixedHashMap::entry`.
+pub enum HashMapEntry<'b, 'a: 'b, K: 'a, V: 'a, S: 'b> {
+    /// The key is present in the map.
+    Occupied(HashMapOccupiedEntry<'b, 'a, K, V, S>),
+    /// The key is not present in the map.
+    Vacant(HashMapVacantEntry<'b, 'a, K, V, S>),
+}
+
+impl<'b, 'a, K, V, S> HashMapEntry<'b, 'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            HashMapEntry::Occupied(ref entry) => entry.key(),
+            HashMapEntry::Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// Returns a mutable reference to the value, inserting `default` first
+    /// if the key is not present.
+    ///
+    /// If the map is full, the pair is handed back inside the error.
+    pub fn or_insert(self, default: V) -> Result<&'b mut V, CapacityError<(K, V)>> {
+        match self {
+            HashMapEntry::Occupied(entry) => Ok(entry.into_mut()),
+            HashMapEntry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Returns a mutable reference to the value, inserting the result of
+    /// `default` first if the key is not present.
+    ///
+    /// If the map is full, the pair is handed back inside the error.
+    pub fn or_insert_with<F>(self, default: F) -> Result<&'b mut V, CapacityError<(K, V)>>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            HashMapEntry::Occupied(entry) => Ok(entry.into_mut()),
+            HashMapEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the value if the key is present, and returns the entry.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            HashMapEntry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                HashMapEntry::Occupied(entry)
+            }
+            HashMapEntry::Vacant(entry) => HashMapEntry::Vacant(entry),
+        }
+    }
+}
+
+/// An entry for a key that is present in a `FixedHashMap`.
+pub struct HashMapOccupiedEntry<'b, 'a: 'b, K: 'a, V: 'a, S: 'b> {
+    map: &'b mut FixedHashMap<'a, K, V, S>,
+    index: usize,
+}
+
+impl<'b, 'a, K, V, S> HashMapOccupiedEntry<'b, 'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn entry(&self) -> &(K, V) {
+        self.map.buckets[self.index]
+            .as_ref()
+            .expect("bucket is occupied")
+    }
+
+    fn entry_mut(&mut self) -> &mut (K, V) {
+        self.map.buckets[self.index]
+            .as_mut()
+            .expect("bucket is occupied")
+    }
+
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.entry().0
+    }
+
+    /// Returns a reference to the value of the entry.
+    pub fn get(&self) -> &V {
+        &self.entry().1
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.entry_mut().1
+    }
+
+    /// Returns a mutable reference to the value of the entry, with the
+    /// lifetime of the map.
+    pub fn into_mut(self) -> &'b mut V {
+        let entry = self.map.buckets[self.index]
+            .as_mut()
+            .expect("bucket is occupied");
+        &mut entry.1
+    }
+
+    /// Replaces the value of the entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, returning its value.
+    pub fn remove(self) -> V {
+        self.map.take_bucket(self.index).1
+    }
+}
+
+/// An entry for a key that is not present in a `FixedHashMap`.
+pub struct HashMapVacantEntry<'b, 'a: 'b, K: 'a, V: 'a, S: 'b> {
+    map: &'b mut FixedHashMap<'a, K, V, S>,
+    key: K,
+}
+
+impl<'b, 'a, K, V, S> HashMapVacantEntry<'b, 'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the key of the entry, consuming it.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the key with `value`, returning a mutable reference to the
+    /// value.
+    ///
+    /// If the map is full, the pair is handed back inside the error.
+    pub fn insert(self, value: V) -> Result<&'b mut V, CapacityError<(K, V)>> {
+        let index = self.map.place(self.key, value)?;
+        let entry = self.map.buckets[index]
+            .as_mut()
+            .expect("bucket is occupied");
+        Ok(&mut entry.1)
+    }
+}
+
+/// An iterator over the entries of a `FixedHashMap`, created by
+/// `FixedHashMap::iter`.
+pub struct HashMapIter<'b, K: 'b, V: 'b> {
+    inner: slice::Iter<'b, Option<(K, V)>>,
+    remaining: usize,
+}
+
+impl<'b, K, V> Iterator for HashMapIter<'b, K, V> {
+    type Item = (&'b K, &'b V);
+
+    fn next(&mut self) -> Option<(&'b K, &'b V)> {
+        for bucket in &mut self.inner {
+            if let Some((ref k, ref v)) = *bucket {
+                self.remaining -= 1;
+                return Some((k, v));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for HashMapIter<'b, K, V> {}
+
+/// An iterator over the entries of a `FixedHashMap` with mutable references
+/// to the values, created by `FixedHashMap::iter_mut`.
+pub struct HashMapIterMut<'b, K: 'b, V: 'b> {
+    inner: slice::IterMut<'b, Option<(K, V)>>,
+    remaining: usize,
+}
+
+impl<'b, K, V> Iterator for HashMapIterMut<'b, K, V> {
+    type Item = (&'b K, &'b mut V);
+
+    fn next(&mut self) -> Option<(&'b K, &'b mut V)> {
+        for bucket in &mut self.inner {
+            if let Some((ref k, ref mut v)) = *bucket {
+                self.remaining -= 1;
+                return Some((k, v));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for HashMapIterMut<'b, K, V> {}
+
+impl<'a, K, V, S> Drop for FixedHashMap<'a, K, V, S> {
+    fn drop(&mut self) {
+        // Buckets from `from_uninit` would otherwise leak their entries.
+        for bucket in self.buckets.iter_mut() {
+            *bucket = None;
+        }
+    }
+}
+
+impl<'a, K, V, S> fmt::Debug for FixedHashMap<'a, K, V, S>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.buckets
+                    .iter()
+                    .filter_map(|bucket| bucket.as_ref().map(|entry| (&entry.0, &entry.1))),
+            )
+            .finish()
+    }
+}
+
+impl<'a, 'b, K, V, S> IntoIterator for &'b FixedHashMap<'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    type Item = (&'b K, &'b V);
+    type IntoIter = HashMapIter<'b, K, V>;
+
+    fn into_iter(self) -> HashMapIter<'b, K, V> {
+        self.iter()
+    }
+}
+
+// Stops at the first new key that does not fit.
+impl<'a, K, V, S> Extend<(K, V)> for FixedHashMap<'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        for (key, value) in iterable {
+            if self.insert(key, value).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a, 'q, K, V, S, Q> ops::Index<&'q Q> for FixedHashMap<'a, K, V, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Returns the value for the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedHashMap;
+    use core::hash::{BuildHasherDefault, Hasher};
+    use std::prelude::v1::*;
+    use std::rc::Rc;
+
+    /// Hashes every key to its value modulo 8, so that tests control where
+    /// entries collide.
+    #[derive(Default)]
+    struct ModHasher(u64);
+
+    impl Hasher for ModHasher {
+        fn finish(&self) -> u64 {
+            self.0 % 8
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.0 = (self.0 << 8) | u64::from(b);
+            }
+        }
+
+        fn write_u32(&mut self, n: u32) {
+            self.0 = u64::from(n);
+        }
+    }
+
+    type ModMap<'a, V> = FixedHashMap<'a, u32, V, BuildHasherDefault<ModHasher>>;
+
+    #[test]
+    fn test_remove_keeps_chain_reachable() {
+        let mut buckets = alloc_stack!([Option<(u32, u32)>; 8]);
+        let mut map: ModMap<u32> = FixedHashMap::new(&mut buckets);
+        // 0, 8 and 16 all want bucket 0; 1 wants bucket 1 but is pushed along
+        map.extend(vec![(0, 0), (1, 1), (8, 8), (16, 16), (7, 7), (15, 15)]);
+        assert_eq!(map.len(), 6);
+
+        assert_eq!(map.remove(&0), Some(0));
+        assert_eq!(map.remove(&7), Some(7));
+        for &k in &[1, 8, 16, 15] {
+            assert_eq!(map.get(&k), Some(&k), "key {}", k);
+        }
+        assert_eq!(map.get(&0), None);
+        assert_eq!(map.get(&24), None);
+    }
+
+    #[test]
+    fn test_full_map() {
+        let mut buckets = alloc_stack!([Option<(u32, u32)>; 8]);
+        let mut map: ModMap<u32> = FixedHashMap::new(&mut buckets);
+        for k in 0..8 {
+            map.insert(k * 8, k).unwrap();
+        }
+        assert!(map.insert(100, 0).is_err());
+        // Lookups of missing keys terminate even with no empty bucket
+        assert_eq!(map.get(&100), None);
+        assert_eq!(map.iter().len(), 8);
+
+        *map.entry(56).or_insert(0).unwrap() += 10;
+        assert_eq!(map[&56], 17);
+        assert!(map.entry(99).or_insert(0).is_err());
+    }
+
+    #[test]
+    fn test_drop_entries() {
+        let counter = Rc::new(());
+        {
+            let mut buckets = alloc_stack_uninit!([Option<(u32, Rc<()>)>; 4]);
+            let mut map: ModMap<Rc<()>> = FixedHashMap::from_uninit(&mut buckets);
+            for k in 0..3 {
+                map.insert(k, counter.clone()).unwrap();
+            }
+            map.insert(1, counter.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&counter), 4);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,598 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A priority queue with fixed capacity, implemented as a binary heap.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops;
+
+use super::{CapacityError, FixedVec, Iter};
+
+/// The order in which a `FixedBinaryHeap` returns its elements.
+///
+/// This is implemented by `Max` and `Min`.
+pub trait HeapKind {
+    /// How an element compares to the elements below it in the heap.
+    #[doc(hidden)]
+    const ORDERING: Ordering;
+}
+
+/// Marks a `FixedBinaryHeap` that returns the greatest element first.
+#[derive(Debug)]
+pub enum Max {}
+
+/// Marks a `FixedBinaryHeap` that returns the least element first.
+#[derive(Debug)]
+pub enum Min {}
+
+impl HeapKind for Max {
+    const ORDERING: Ordering = Ordering::Greater;
+}
+
+impl HeapKind for Min {
+    const ORDERING: Ordering = Ordering::Less;
+}
+
+/// A priority queue that stores its elements in a `FixedVec`.
+///
+/// Elements are added and removed in O(log n) time. By default, the greatest
+/// element is returned first; use the `_min` constructors for a heap that
+/// returns the least element first.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedBinaryHeap;
+/// # fn main() {
+/// // Timers ordered by deadline, earliest first
+/// let mut space = alloc_stack!([(u32, u8); 8]);
+/// let mut timers = FixedBinaryHeap::new_min(&mut space);
+///
+/// timers.push((300, 1)).unwrap();
+/// timers.push((100, 2)).unwrap();
+/// timers.push((200, 3)).unwrap();
+///
+/// assert_eq!(timers.peek(), Some(&(100, 2)));
+/// assert_eq!(timers.pop(), Some((100, 2)));
+/// assert_eq!(timers.pop(), Some((200, 3)));
+/// assert_eq!(timers.pop(), Some((300, 1)));
+/// assert_eq!(timers.pop(), None);
+/// # }
+/// ```
+pub struct FixedBinaryHeap<'a, T: 'a, K = Max> {
+    vec: FixedVec<'a, T>,
+    kind: PhantomData<K>,
+}
+
+impl<'a, T> FixedBinaryHeap<'a, T, Max>
+where
+    T: 'a + Ord,
+{
+    /// Create a new, empty max-heap from the provided slice, in the process
+    /// taking ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way; see `FixedVec::new`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(5).unwrap();
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [T]) -> Self
+    where
+        T: Copy,
+    {
+        FixedBinaryHeap::from_vec(FixedVec::new(memory))
+    }
+
+    /// Create a new, empty max-heap from the provided slice of uninitialized
+    /// memory, in the process taking ownership of the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([String; 4]);
+    /// let mut heap = FixedBinaryHeap::from_uninit(&mut space);
+    /// heap.push(String::from("a")).unwrap();
+    /// heap.push(String::from("b")).unwrap();
+    /// assert_eq!(heap.pop().unwrap(), "b");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedBinaryHeap::from_vec(FixedVec::from_uninit(memory))
+    }
+
+    /// Turns a `FixedVec` into a max-heap, keeping its elements and its
+    /// memory.
+    ///
+    /// This takes O(n) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedBinaryHeap, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[3, 1, 4, 1, 5]).unwrap();
+    ///
+    /// let mut heap = FixedBinaryHeap::from_vec(vec);
+    /// assert_eq!(heap.pop(), Some(5));
+    /// assert_eq!(heap.len(), 4);
+    /// # }
+    /// ```
+    pub fn from_vec(vec: FixedVec<'a, T>) -> Self {
+        FixedBinaryHeap::heapify(vec)
+    }
+}
+
+impl<'a, T> FixedBinaryHeap<'a, T, Min>
+where
+    T: 'a + Ord,
+{
+    /// Create a new, empty min-heap from the provided slice, in the process
+    /// taking ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way; see `FixedVec::new`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut heap = FixedBinaryHeap::new_min(&mut space);
+    /// heap.push(5).unwrap();
+    /// heap.push(1).unwrap();
+    /// assert_eq!(heap.peek(), Some(&1));
+    /// # }
+    /// ```
+    pub fn new_min(memory: &'a mut [T]) -> Self
+    where
+        T: Copy,
+    {
+        FixedBinaryHeap::from_vec_min(FixedVec::new(memory))
+    }
+
+    /// Create a new, empty min-heap from the provided slice of uninitialized
+    /// memory, in the process taking ownership of the slice.
+    pub fn from_uninit_min(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedBinaryHeap::from_vec_min(FixedVec::from_uninit(memory))
+    }
+
+    /// Turns a `FixedVec` into a min-heap, keeping its elements and its
+    /// memory.
+    ///
+    /// This takes O(n) time.
+    pub fn from_vec_min(vec: FixedVec<'a, T>) -> Self {
+        FixedBinaryHeap::heapify(vec)
+    }
+}
+
+impl<'a, T, K> FixedBinaryHeap<'a, T, K>
+where
+    T: 'a + Ord,
+    K: HeapKind,
+{
+    fn heapify(vec: FixedVec<'a, T>) -> Self {
+        let mut heap = FixedBinaryHeap {
+            vec,
+            kind: PhantomData,
+        };
+        let len = heap.len();
+        for i in (0..len / 2).rev() {
+            heap.sift_down(i, len);
+        }
+        heap
+    }
+
+    /// Returns `true` if `a` belongs above `b` in the heap.
+    #[inline]
+    fn is_above(a: &T, b: &T) -> bool {
+        a.cmp(b) == K::ORDERING
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        let v = self.vec.as_mut_slice();
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if !Self::is_above(&v[pos], &v[parent]) {
+                break;
+            }
+            v.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    /// Moves the element at `pos` down until it is in heap order, only
+    /// considering the first `end` elements.
+    fn sift_down(&mut self, mut pos: usize, end: usize) {
+        let v = self.vec.as_mut_slice();
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && Self::is_above(&v[child + 1], &v[child]) {
+                child += 1;
+            }
+            if !Self::is_above(&v[child], &v[pos]) {
+                break;
+            }
+            v.swap(pos, child);
+            pos = child;
+        }
+    }
+
+    /// Returns the capacity of the heap.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of elements in the heap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of elements that can be added before the heap is
+    /// full.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.vec.available()
+    }
+
+    /// Returns `true` if the heap contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Adds an element to the heap.
+    ///
+    /// If the heap is full, the element is handed back inside the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(2).unwrap();
+    /// assert_eq!(heap.push(3).unwrap_err().into_inner(), 3);
+    /// # }
+    /// ```
+    pub fn push(&mut self, item: T) -> Result<(), CapacityError<T>> {
+        self.vec.push(item)?;
+        let last = self.len() - 1;
+        self.sift_up(last);
+        Ok(())
+    }
+
+    /// Removes the first element of the heap and returns it, or `None` if the
+    /// heap is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(3).unwrap();
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), None);
+    /// # }
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let item = self.vec.swap_remove(0);
+        let len = self.len();
+        self.sift_down(0, len);
+        Some(item)
+    }
+
+    /// Returns a reference to the first element of the heap, or `None` if the
+    /// heap is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// assert_eq!(heap.peek(), None);
+    /// heap.push(2).unwrap();
+    /// assert_eq!(heap.peek(), Some(&2));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.vec.get(0)
+    }
+
+    /// Returns a mutable reference to the first element of the heap, or
+    /// `None` if the heap is empty.
+    ///
+    /// The heap is put back in order when the returned `PeekMut` is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedBinaryHeap, PeekMut};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(5).unwrap();
+    /// heap.push(3).unwrap();
+    ///
+    /// *heap.peek_mut().unwrap() = 1;
+    /// assert_eq!(heap.peek(), Some(&3));
+    ///
+    /// // The element can also be removed through the `PeekMut`
+    /// let top = heap.peek_mut().unwrap();
+    /// assert_eq!(PeekMut::pop(top), 3);
+    /// assert_eq!(heap.len(), 1);
+    /// # }
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, 'a, T, K>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self })
+        }
+    }
+
+    /// Removes all elements from the heap.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Returns the elements of the heap as a slice, in no particular order.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    /// Returns an iterator over the elements of the heap, in no particular
+    /// order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.vec.iter()
+    }
+
+    /// Returns the underlying vector, with the elements in no particular
+    /// order.
+    #[inline]
+    pub fn into_vec(self) -> FixedVec<'a, T> {
+        self.vec
+    }
+
+    /// Returns the underlying vector, with the elements sorted in ascending
+    /// order.
+    ///
+    /// The elements are sorted in place, in O(n log n) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.extend([3, 1, 4, 1, 5].iter().cloned());
+    ///
+    /// let vec = heap.into_sorted();
+    /// assert_eq!(vec.as_slice(), &[1, 1, 3, 4, 5]);
+    /// # }
+    /// ```
+    pub fn into_sorted(mut self) -> FixedVec<'a, T> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.vec.as_mut_slice().swap(0, end);
+            self.sift_down(0, end);
+        }
+        // Taking elements from the top of a min-heap leaves them in
+        // descending order.
+        if K::ORDERING == Ordering::Less {
+            self.vec.as_mut_slice().reverse();
+        }
+        self.vec
+    }
+}
+
+/// A mutable reference to the first element of a `FixedBinaryHeap`, created
+/// by `FixedBinaryHeap::peek_mut`.
+///
+/// When it is dropped, the element is moved to its proper place in the heap.
+pub struct PeekMut<'b, 'a: 'b, T: 'a + Ord, K: HeapKind> {
+    heap: &'b mut FixedBinaryHeap<'a, T, K>,
+}
+
+impl<'b, 'a, T, K> PeekMut<'b, 'a, T, K>
+where
+    T: Ord,
+    K: HeapKind,
+{
+    /// Removes the peeked element from the heap and returns it.
+    pub fn pop(this: PeekMut<'b, 'a, T, K>) -> T {
+        // Dropping `this` then restores the order, as in `FixedBinaryHeap::pop`.
+        this.heap.vec.swap_remove(0)
+    }
+}
+
+impl<'b, 'a, T, K> Drop for PeekMut<'b, 'a, T, K>
+where
+    T: Ord,
+    K: HeapKind,
+{
+    fn drop(&mut self) {
+        let len = self.heap.len();
+        self.heap.sift_down(0, len);
+    }
+}
+
+impl<'b, 'a, T, K> ops::Deref for PeekMut<'b, 'a, T, K>
+where
+    T: Ord,
+    K: HeapKind,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.vec[0]
+    }
+}
+
+impl<'b, 'a, T, K> ops::DerefMut for PeekMut<'b, 'a, T, K>
+where
+    T: Ord,
+    K: HeapKind,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.heap.vec[0]
+    }
+}
+
+impl<'a, T, K> fmt::Debug for FixedBinaryHeap<'a, T, K>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.vec, f)
+    }
+}
+
+// Like `FixedVec`, stops once the heap is full.
+impl<'a, T, K> Extend<T> for FixedBinaryHeap<'a, T, K>
+where
+    T: Ord,
+    K: HeapKind,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        let mut iter = iterable.into_iter();
+        while self.available() > 0 {
+            match iter.next() {
+                Some(item) => {
+                    let _ = self.push(item);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedBinaryHeap;
+    use std::prelude::v1::*;
+    use FixedVec;
+
+    const INPUT: [u32; 12] = [7, 3, 9, 3, 0, 12, 5, 8, 1, 11, 6, 2];
+
+    #[test]
+    fn test_pop_order() {
+        let mut space = alloc_stack!([u32; 16]);
+        let mut heap = FixedBinaryHeap::new(&mut space);
+        heap.extend(INPUT.iter().cloned());
+        let mut expected = INPUT.to_vec();
+        expected.sort_by(|a, b| b.cmp(a));
+        for x in expected {
+            assert_eq!(heap.pop(), Some(x));
+        }
+        assert!(heap.is_empty());
+
+        let mut space = alloc_stack!([u32; 16]);
+        let mut heap = FixedBinaryHeap::new_min(&mut space);
+        heap.extend(INPUT.iter().cloned());
+        let mut expected = INPUT.to_vec();
+        expected.sort();
+        for x in expected {
+            assert_eq!(heap.pop(), Some(x));
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_into_sorted() {
+        let mut expected = INPUT.to_vec();
+        expected.sort();
+
+        let mut space = alloc_stack!([u32; 16]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&INPUT).unwrap();
+        let heap = FixedBinaryHeap::from_vec(vec);
+        assert_eq!(heap.into_sorted(), expected[..]);
+
+        let mut space = alloc_stack!([u32; 16]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&INPUT).unwrap();
+        let heap = FixedBinaryHeap::from_vec_min(vec);
+        assert_eq!(heap.into_sorted(), expected[..]);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut space = alloc_stack!([u32; 16]);
+        let mut heap = FixedBinaryHeap::new_min(&mut space);
+        heap.extend(INPUT.iter().cloned());
+        // Reschedule the earliest entries to after all the others
+        for _ in 0..3 {
+            *heap.peek_mut().unwrap() += 100;
+        }
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.into_sorted().as_slice()[7..], [100, 101, 102]);
+    }
+}
@@ -40,9 +40,14 @@
 //!
 //! # Differences from `std::vec::Vec`
 //!
-//! For now, `FixedVec` only works for types that implement `Copy`. This
-//! requirement will be lifted in the future, but for now it is the most
-//! straightforward way to get to a minimum viable product.
+//! A `FixedVec` can hold any type. For `Copy` types, `FixedVec::new` can
+//! manage an ordinary slice such as the one produced by `alloc_stack!`. For
+//! other types, the backing memory must be a slice of `MaybeUninit<T>`, such
+//! as the one produced by `alloc_stack_uninit!`, and the vector is created
+//! with `FixedVec::from_uninit`. This also avoids initializing large buffers
+//! that will be filled in later anyway. In both cases the vector
+//! owns the elements it contains: they are dropped when they are removed
+//! (`pop`, `clear`, `retain`, ...) or when the `FixedVec` itself is dropped.
 //!
 //! Although every effort has been made to mimic the functionality of `Vec`,
 //! this is not a perfect clone. Specifically, functions that require memory
@@ -55,6 +60,16 @@
 //! valid for the underlying memory_. So, for example, if a `FixedVec` were
 //! allocated with 10 elements, and 3 new elements were pushed to it, accessing
 //! index 5 would panic, even though accessing that memory would be safe.
+//! Ranges can be used for indexing too, as with slices, so `&vec[1..3]`
+//! and `&vec[..]` are also restricted to the elements in the vector.
+//!
+//! `FixedVec` dereferences to a slice of its elements, so the methods of
+//! slices such as `sort`, `contains` or `chunks` can be used on it directly,
+//! and a `&FixedVec` can be passed wherever a `&[T]` is expected.
+//!
+//! Where panicking is not acceptable, each of the functions that panic on an
+//! invalid index or length has a `try_` counterpart, such as `try_insert` and
+//! `try_remove`, which returns an error instead.
 //!
 //! ## Functions with different signatures
 //!
@@ -62,14 +77,28 @@
 //! `Vec`.
 //!
 //! * `new`: Self-explanatory - instantiating a different object
-//! * `push`, `push_all`, `insert`: Functions that add elements return a Result
-//!    indicating if the result was successful.
+//! * `push`, `push_all`, `insert`, `append`: Functions that add elements
+//!   return a Result indicating if the result was successful. When a single
+//!   element is rejected, it is handed back inside the `CapacityError`.
 //! * `map_in_place`: Similar to `Vec` `map_in_place`, except there is no
-//!    coercion of the types.
+//!   coercion of the types.
 //!
 //! ## Functions in `FixedVec` not in `Vec`
 //!
 //! * `available`: Convenience function for checking remaining space.
+//! * `from_uninit`: Creates a `FixedVec` over uninitialized memory.
+//! * `from_initialized`: Creates a `FixedVec` over memory that already holds
+//!   a number of valid elements.
+//! * `extract_if`: Removes the elements matching a predicate, yielding them
+//!   through an iterator.
+//! * `split_off_into`: Like `Vec` `split_off`, except the split-off elements
+//!   are moved into another `FixedVec` rather than newly allocated memory.
+//! * `try_insert`, `try_remove`, `try_swap_remove`, `try_resize`: Versions of
+//!   `insert`, `remove`, `swap_remove` and `resize` that return an error
+//!   instead of panicking.
+//! * `as_str`: For a `FixedVec<u8>`, views the contents as a string slice.
+//!   Such a vector also implements `fmt::Write`, so it can be used as the
+//!   target of `write!`.
 //! * `iter`: `FixedVec` cannot implement `IntoIterator` because the type
 //!   signature of that trait requires taking ownership of the underlying
 //!   struct. Since `FixedVec` keeps a reference to its backing store,
@@ -89,12 +118,71 @@
 //! * `reserve_exact`
 //! * `shrink_to_fit`
 //! * `into_boxed_slice`
-//! * `truncate`
-//! * `set_len`
-//! * `append`
-//! * `drain`
 //! * `split_off`
 //!
+//! # Owned storage
+//!
+//! `FixedVec` borrows its memory, which ties it to the lifetime of that
+//! memory. When that is inconvenient, `FixedArrayVec` provides the same
+//! interface over an array it owns, and can be borrowed as a `FixedVec` when
+//! needed.
+//!
+//! # Strings
+//!
+//! `FixedString` wraps a `FixedVec<u8>` and keeps its contents valid UTF-8,
+//! giving a string type that works with the same preallocated memory.
+//!
+//! # Queues
+//!
+//! Removing the first element of a `FixedVec` shifts all the others. For
+//! FIFO queues, `FixedDeque` keeps its elements in a ring buffer over the
+//! same kind of preallocated memory, so that both ends can be pushed to and
+//! popped from in constant time.
+//!
+//! To pass elements between an interrupt handler and the main loop,
+//! `SpscQueue` splits into a `Producer` and a `Consumer` that can be used
+//! from different contexts without locking. `spsc_static!` places the queue
+//! in a `static`, so that the halves can be handed to an interrupt handler.
+//!
+//! `FixedBinaryHeap` is a priority queue on top of `FixedVec`, returning
+//! either the greatest or the least element first.
+//!
+//! # Sets and maps
+//!
+//! `FixedSortedSet` and `FixedSortedMap` keep a `FixedVec` sorted, and look
+//! up elements with binary search. They suit small lookup tables that would
+//! otherwise be a `FixedVec` kept sorted by hand.
+//!
+//! For constant-time lookups, `FixedHashMap` is a hash map over an array of
+//! buckets provided by the caller.
+//!
+//! # Pools
+//!
+//! Rather than declaring separate memory for every `FixedVec`,
+//! `FixedVecPool` hands out vectors of any capacity from one block of memory,
+//! and reclaims them all at once with `reset`.
+//!
+//! # Static memory
+//!
+//! `alloc_static!` places the memory for a `FixedVec` in a `static` rather
+//! than on the stack, giving a `FixedVec<'static, T>` that can be stored
+//! anywhere, including in state shared with interrupt handlers. The memory
+//! can be placed in a chosen linker section and aligned, for buffers that
+//! must live in DMA-capable or retained RAM.
+//!
+//! Memory that survives a reset can hold a `PersistentVec`, which stores a
+//! checksummed header next to its elements so that `recover` can tell
+//! whether the memory still holds valid contents.
+//!
+//! # Cargo features
+//!
+//! * `alloc`: Allows comparing `FixedVec` with `Vec`.
+//! * `std`: Implements `std::error::Error` for the error types, and
+//!   `std::io::Write` for `FixedVec<u8>`. Implies `alloc`.
+//! * `serde`: Implements `Serialize` for `FixedVec` and `FixedArrayVec`,
+//!   `Deserialize` for `FixedArrayVec`, and provides `FixedVecSeed` for
+//!   deserializing into an existing `FixedVec`.
+//!
 //! # Example
 //!
 //! Typical usage looks like the following:
@@ -124,13 +212,28 @@
 //! Rust book section ["No stdlib"](https://doc.rust-lang.org/book/no-stdlib.html)
 //! for instructions on building executables using only libcore.
 
+use core::borrow::{Borrow, BorrowMut};
+use core::cell::UnsafeCell;
+use core::cmp::Ordering;
+use core::fmt;
 use core::hash::{Hash, Hasher};
-use core::ops;
+use core::mem::{self, MaybeUninit};
+use core::ops::{self, Bound, RangeBounds};
+use core::ptr;
+use core::slice::{self, SliceIndex};
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
+
 /// Convenience macro for use with `FixedVec`. Allocates the specified number
 /// of elements of specified type on the stack.
 ///
@@ -155,16 +258,375 @@ macro_rules! alloc_stack {
     }};
 }
 
+/// Convenience macro for use with `FixedVec::from_uninit`. Allocates the
+/// specified number of elements of specified type on the stack, without
+/// initializing them.
+///
+/// Unlike `alloc_stack!`, the element type does not need to implement
+/// `Default` or `Copy`, and no time is spent filling the memory.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedVec;
+/// # fn main() {
+/// // Allocate space for 1024 u32's, without zeroing it first
+/// let mut space = alloc_stack_uninit!([u32; 1024]);
+///
+/// // Only pushed elements are visible through the `FixedVec`
+/// let mut vec = FixedVec::from_uninit(&mut space);
+/// vec.push(7).unwrap();
+/// assert_eq!(vec.as_slice(), &[7]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! alloc_stack_uninit {
+    ([$item_type:ty; $len:expr]) => {{
+        $crate::uninit_array::<$item_type, { $len }>()
+    }};
+}
+
+/// Returns an uninitialized array, for use by `alloc_stack_uninit!`.
+#[doc(hidden)]
+#[inline]
+pub const fn uninit_array<T, const N: usize>() -> [MaybeUninit<T>; N] {
+    // An array of `MaybeUninit` does not require initialization.
+    unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() }
+}
+
+/// Creates an `SpscQueue` of the specified number of elements of specified
+/// type in a `static`, and returns its `Producer` and `Consumer` halves.
+///
+/// Both halves borrow the queue for `'static`, so they can be handed to an
+/// interrupt handler or moved to another thread. They are returned only on
+/// the first call of a given `spsc_static!` invocation; every later call
+/// returns `None`.
+///
+/// Checking for an earlier call uses an atomic swap, so this macro needs a
+/// target with atomic read-modify-write instructions.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use std::thread;
+/// # fn main() {
+/// let (mut producer, mut consumer) = spsc_static!([u8; 16]).unwrap();
+/// thread::spawn(move || producer.enqueue(42).unwrap())
+///     .join()
+///     .unwrap();
+/// assert_eq!(consumer.dequeue(), Some(42));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! spsc_static {
+    ([$item_type:ty; $len:expr]) => {{
+        static TAKEN: $crate::__AtomicBool = $crate::__AtomicBool::new(false);
+        static MEMORY: $crate::StaticBuffer<$item_type, { $len }> = $crate::StaticBuffer::new();
+        static QUEUE: $crate::SpscQueue<'static, $item_type> =
+            $crate::SpscQueue::from_static(&MEMORY);
+        if TAKEN.swap(true, $crate::__AtomicOrdering::AcqRel) {
+            None
+        } else {
+            Some(unsafe { QUEUE.split_static() })
+        }
+    }};
+}
+
+/// Allocates the specified number of elements of specified type in a
+/// `static`, and returns a `FixedVec<'static, T>` that manages them.
+///
+/// Since the memory is not on the stack, the vector can outlive the function
+/// that created it, and can be handed to an interrupt handler. The vector is
+/// returned only on the first call of a given `alloc_static!` invocation;
+/// later calls return `None`, so the memory is never managed twice.
+///
+/// Attributes placed before the array type are applied to the `static`
+/// holding the memory. This can be used to choose the linker section the
+/// memory is placed in, such as a RAM bank reachable by DMA. An alignment in
+/// bytes can be given after the array type as `align = N`, where `N` is a
+/// power of two.
+///
+/// The memory starts out uninitialized, so the element type does not need to
+/// implement `Default` or `Copy`. It does need to implement `Send`. Since
+/// nothing is written to the memory before the vector is returned, it can be
+/// placed in a section that is not initialized at startup, such as
+/// `.noinit`.
+///
+/// Checking for an earlier call uses an atomic swap, so the macro is only
+/// available on targets with atomic read-modify-write instructions.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedVec;
+/// # fn main() {
+/// fn log_buffer() -> Option<FixedVec<'static, u8>> {
+///     alloc_static!([u8; 256])
+/// }
+///
+/// let mut log = log_buffer().unwrap();
+/// log.push_all(b"boot").unwrap();
+/// assert_eq!(log.capacity(), 256);
+///
+/// // The memory is already in use
+/// assert!(log_buffer().is_none());
+/// # }
+/// ```
+///
+/// Placing the memory in a specific linker section:
+///
+/// ```ignore
+/// let mut samples = alloc_static!(#[link_section = ".ccmram"] [u16; 4096]).unwrap();
+///
+/// // DMA buffers aligned to a cache line
+/// let mut rx = alloc_static!(#[link_section = ".dtcm"] [u8; 512], align = 32).unwrap();
+/// ```
+#[macro_export]
+macro_rules! alloc_static {
+    ($(#[$attr:meta])* [$item_type:ty; $len:expr]) => {{
+        // The flag is kept apart from the memory, so that attributes such as
+        // `link_section` do not affect it.
+        static TAKEN: $crate::__AtomicBool = $crate::__AtomicBool::new(false);
+        $(#[$attr])*
+        static MEMORY: $crate::StaticBuffer<$item_type, { $len }> = $crate::StaticBuffer::new();
+        if TAKEN.swap(true, $crate::__AtomicOrdering::AcqRel) {
+            None
+        } else {
+            // The flag ensures the memory is only borrowed once.
+            Some(unsafe { MEMORY.take() })
+        }
+    }};
+    ($(#[$attr:meta])* [$item_type:ty; $len:expr], align = $align:literal) => {{
+        #[repr(align($align))]
+        struct Aligned<B>(B);
+
+        static TAKEN: $crate::__AtomicBool = $crate::__AtomicBool::new(false);
+        $(#[$attr])*
+        static MEMORY: Aligned<$crate::StaticBuffer<$item_type, { $len }>> =
+            Aligned($crate::StaticBuffer::new());
+        if TAKEN.swap(true, $crate::__AtomicOrdering::AcqRel) {
+            None
+        } else {
+            Some(unsafe { MEMORY.0.take() })
+        }
+    }};
+}
+
+#[doc(hidden)]
+pub use core::sync::atomic::{AtomicBool as __AtomicBool, Ordering as __AtomicOrdering};
+
+/// Memory in a `static`, for use by `alloc_static!` and `spsc_static!`.
+#[doc(hidden)]
+pub struct StaticBuffer<T, const N: usize> {
+    memory: UnsafeCell<[MaybeUninit<T>; N]>,
+}
+
+// The memory is only ever reachable through what the macro that declared the
+// buffer hands out, once.
+unsafe impl<T: Send, const N: usize> Sync for StaticBuffer<T, N> {}
+
+impl<T, const N: usize> StaticBuffer<T, N> {
+    #[doc(hidden)]
+    pub const fn new() -> Self {
+        StaticBuffer {
+            memory: UnsafeCell::new(uninit_array()),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Must be called at most once for each buffer.
+    #[doc(hidden)]
+    pub unsafe fn take(&'static self) -> FixedVec<'static, T> {
+        FixedVec::from_uninit(&mut *self.memory.get())
+    }
+}
+
+// Implements `PartialEq` between two sequence types by comparing them as
+// slices, allowing the element types to differ.
+macro_rules! impl_slice_eq {
+    ([$($generics:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<$($generics)*> PartialEq<$rhs> for $lhs
+        where
+            A: PartialEq<B>,
+        {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                self[..] == other[..]
+            }
+        }
+    };
+}
+
+mod array;
+mod deque;
+mod hash_map;
+mod heap;
+mod persistent;
+mod pool;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod sorted;
+mod spsc;
+mod string;
+
+pub use array::{FixedArrayVec, FixedVecView};
+pub use deque::{DequeIter, DequeIterMut, FixedDeque};
+pub use hash_map::{
+    FixedHashMap, HashMapEntry, HashMapIter, HashMapIterMut, HashMapOccupiedEntry,
+    HashMapVacantEntry,
+};
+pub use heap::{FixedBinaryHeap, HeapKind, Max, Min, PeekMut};
+pub use persistent::{Persistable, PersistentVec};
+pub use pool::FixedVecPool;
+#[cfg(feature = "serde")]
+pub use serde_impl::FixedVecSeed;
+pub use sorted::{
+    Entry, FixedSortedMap, FixedSortedSet, MapIter, MapIterMut, OccupiedEntry, VacantEntry,
+};
+pub use spsc::{Consumer, Producer, SpscQueue};
+pub use string::FixedString;
+
 pub type Result<T> = core::result::Result<T, ErrorKind>;
 
-#[derive(Debug)]
+/// The reasons an operation on a `FixedVec` can fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ErrorKind {
+    /// There was no space left for another element.
     NoSpace,
+    /// A bulk operation needed more space than was available. No elements
+    /// were added.
+    CapacityExceeded {
+        /// The number of elements the operation tried to add.
+        requested: usize,
+        /// The number of elements there was space for.
+        available: usize,
+    },
+    /// An index was outside of the bounds of the vector.
+    IndexOutOfBounds {
+        /// The offending index.
+        index: usize,
+        /// The length of the vector at the time.
+        len: usize,
+    },
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::NoSpace => f.write_str("no space left for another element"),
+            ErrorKind::CapacityExceeded {
+                requested,
+                available,
+            } => write!(
+                f,
+                "space needed for {} elements, but only {} available",
+                requested, available
+            ),
+            ErrorKind::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} is out of bounds for length {}", index, len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorKind {}
+
+/// The error returned when an element could not be added.
+///
+/// Holds on to the rejected element, so that it is not lost. Converts into
+/// `ErrorKind` for use with `?` in functions returning `fixedvec::Result`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::{ErrorKind, FixedVec};
+/// # fn main() {
+/// let mut space = alloc_stack_uninit!([String; 1]);
+/// let mut vec = FixedVec::from_uninit(&mut space);
+/// vec.push(String::from("first")).unwrap();
+///
+/// let err = vec.push(String::from("second")).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::NoSpace);
+/// assert_eq!(err.into_inner(), "second");
+/// # }
+/// ```
+pub struct CapacityError<T> {
+    element: T,
+    kind: ErrorKind,
+}
+
+impl<T> CapacityError<T> {
+    #[inline]
+    fn new(element: T) -> Self {
+        CapacityError {
+            element,
+            kind: ErrorKind::NoSpace,
+        }
+    }
+
+    /// Returns the reason the element was rejected.
+    #[inline]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns a reference to the rejected element.
+    #[inline]
+    pub fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// Returns the rejected element, consuming the error.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.element
+    }
+}
+
+// The element is left out so that errors for any element type can be
+// unwrapped.
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CapacityError")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.kind, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for CapacityError<T> {}
+
+impl<T> From<CapacityError<T>> for ErrorKind {
+    #[inline]
+    fn from(err: CapacityError<T>) -> ErrorKind {
+        err.kind
+    }
+}
+
+/// Lets a container take a slice of `Copy` elements as its memory.
+///
+/// `MaybeUninit<T>` has the same layout as `T`. The containers never write an
+/// uninitialized value to their memory, and `T: Copy` means elements moved out
+/// are never dropped, so the slice stays valid for the caller.
+#[inline]
+fn as_uninit_mut<T: Copy>(memory: &mut [T]) -> &mut [MaybeUninit<T>] {
+    unsafe { &mut *(memory as *mut [T] as *mut [MaybeUninit<T>]) }
 }
 
-#[derive(Debug)]
-pub struct FixedVec<'a, T: 'a + Copy> {
-    memory: &'a mut [T],
+pub struct FixedVec<'a, T: 'a> {
+    memory: &'a mut [MaybeUninit<T>],
     len: usize,
 }
 
@@ -178,6 +640,10 @@ where
     /// Create a new `FixedVec` from the provided slice, in the process taking
     /// ownership of the slice.
     ///
+    /// Only `Copy` types can be managed this way, since the slice still holds
+    /// valid values once the `FixedVec` is gone. Use `from_uninit` for other
+    /// types.
+    ///
     /// # Example
     ///
     /// ```
@@ -193,10 +659,65 @@ where
     /// ```
     ///
     pub fn new(memory: &'a mut [T]) -> Self {
-        FixedVec {
-            memory: memory,
-            len: 0,
-        }
+        FixedVec::from_uninit(as_uninit_mut(memory))
+    }
+
+    /// Create a new `FixedVec` from the provided slice, treating the first
+    /// `len` elements as the contents of the vector.
+    ///
+    /// This allows adopting memory that has already been filled, such as a
+    /// DMA buffer, without copying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than the length of `memory`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// let mut samples = [3, 1, 4, 1, 5, 0, 0, 0];
+    /// let mut vec = FixedVec::from_initialized(&mut samples, 5);
+    /// assert_eq!(vec.as_slice(), &[3, 1, 4, 1, 5]);
+    /// assert_eq!(vec.available(), 3);
+    ///
+    /// vec.push(9).unwrap();
+    /// assert_eq!(vec.as_slice(), &[3, 1, 4, 1, 5, 9]);
+    /// ```
+    pub fn from_initialized(memory: &'a mut [T], len: usize) -> Self {
+        assert!(len <= memory.len());
+        let mut vec = FixedVec::new(memory);
+        vec.len = len;
+        vec
+    }
+}
+
+impl<'a, T> FixedVec<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new `FixedVec` from the provided slice of uninitialized
+    /// memory, in the process taking ownership of the slice.
+    ///
+    /// This works for any element type, including types that are not `Copy`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([String; 4]);
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    /// assert_eq!(vec.capacity(), 4);
+    /// assert_eq!(vec.len(), 0);
+    ///
+    /// vec.push(String::from("hello")).unwrap();
+    /// assert_eq!(vec[0], "hello");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedVec { memory, len: 0 }
     }
 
     /// Returns the capacity of the vector.
@@ -300,7 +821,7 @@ where
     /// # }
     #[inline]
     pub fn as_slice(&self) -> &[T] {
-        &self.memory[..self.len]
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
     }
 
     /// Extracts a mutable slice of the entire vector.
@@ -323,7 +844,93 @@ where
     /// # }
     #[inline]
     pub fn as_mut_slice(&mut self) -> &mut [T] {
-        &mut self.memory[..self.len]
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// This is a low-level operation that maintains none of the normal
+    /// invariants of the type. Normally changing the length of a vector is
+    /// done using one of the safe operations instead, such as `truncate`,
+    /// `resize`, `push_all` or `clear`.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less than or equal to `capacity()`, and the elements
+    /// at `old_len..new_len` must be initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([u8; 16]);
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    ///
+    /// // Fill the spare capacity by some other means...
+    /// for i in 0..4 {
+    ///     unsafe { vec.as_mut_ptr().add(i).write(i as u8) };
+    /// }
+    /// unsafe { vec.set_len(4) };
+    /// assert_eq!(vec.as_slice(), &[0, 1, 2, 3]);
+    /// # }
+    /// ```
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        self.len = new_len;
+    }
+
+    /// Returns a raw pointer to the vector's memory.
+    ///
+    /// The pointer is valid for `capacity()` elements, of which the first
+    /// `len()` are initialized.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.memory.as_ptr() as *const T
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's memory.
+    ///
+    /// The pointer is valid for `capacity()` elements, of which the first
+    /// `len()` are initialized.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.memory.as_mut_ptr() as *mut T
+    }
+
+    /// Returns a write-only view of the unused memory of the vector, after
+    /// the last element.
+    ///
+    /// Values written here become part of the vector once `set_len` is
+    /// called. The view only allows storing initialized values, since the
+    /// memory of a vector created by `new` is handed back to its owner as a
+    /// slice of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push(1).unwrap();
+    ///
+    /// let mut spare = vec.spare_capacity_mut();
+    /// assert_eq!(spare.len(), 7);
+    /// spare.write(0, 2);
+    /// spare.write(1, 3);
+    /// unsafe { vec.set_len(3) };
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> SpareCapacity<'_, T> {
+        SpareCapacity {
+            slots: &mut self.memory[self.len..],
+        }
     }
 
     /// Inserts an element at position `index` within the vector, shifting all
@@ -352,26 +959,70 @@ where
     /// assert_eq!(vec.as_slice(), &[1, 15, 2, 3, 16]);
     ///
     /// // Cannot insert if there is not enough capacity
-    /// assert!(vec.insert(2, 17).is_err());
+    /// let err = vec.insert(2, 17).unwrap_err();
+    /// assert_eq!(err.into_inner(), 17);
+    /// # }
+    pub fn insert(
+        &mut self,
+        index: usize,
+        element: T,
+    ) -> core::result::Result<(), CapacityError<T>> {
+        match self.try_insert(index, element) {
+            Err(ref err) if err.kind() != ErrorKind::NoSpace => panic!("{}", err),
+            result => result,
+        }
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after position `i` one position to the right.
+    ///
+    /// Unlike `insert`, an invalid `index` results in an error rather than a
+    /// panic. Either way, the rejected element is handed back in the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 3]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[1, 2]).unwrap();
+    /// let err = vec.try_insert(3, 15).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::IndexOutOfBounds { index: 3, len: 2 });
+    /// assert_eq!(err.into_inner(), 15);
+    ///
+    /// vec.try_insert(1, 15).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 15, 2]);
+    ///
+    /// let err = vec.try_insert(1, 16).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::NoSpace);
     /// # }
-    pub fn insert(&mut self, index: usize, element: T) -> Result<()> {
-        assert!(index <= self.len);
-        if index == self.len || self.len == 0 {
-            self.push(element)
+    /// ```
+    pub fn try_insert(
+        &mut self,
+        index: usize,
+        element: T,
+    ) -> core::result::Result<(), CapacityError<T>> {
+        if index > self.len {
+            Err(CapacityError {
+                element,
+                kind: ErrorKind::IndexOutOfBounds {
+                    index,
+                    len: self.len,
+                },
+            })
         } else if self.available() >= 1 {
-            self.len += 1;
-            let mut i = self.len;
-            loop {
-                if i == index {
-                    break;
-                }
-                self.memory[i] = self.memory[i - 1];
-                i -= 1;
+            unsafe {
+                let p = self.as_mut_ptr().add(index);
+                ptr::copy(p, p.add(1), self.len - index);
+                ptr::write(p, element);
             }
-            self.memory[index] = element;
+            self.len += 1;
             Ok(())
         } else {
-            Err(ErrorKind::NoSpace)
+            Err(CapacityError::new(element))
         }
     }
 
@@ -401,17 +1052,54 @@ where
     /// assert_eq!(vec.as_slice(), &[1]);
     /// # }
     pub fn remove(&mut self, index: usize) -> T {
-        assert!(index < self.len);
-        let ret = self.memory[index];
-        self.len -= 1;
-        for i in index..self.len {
-            self.memory[i] = self.memory[i + 1];
+        match self.try_remove(index) {
+            Ok(element) => element,
+            Err(err) => panic!("{}", err),
         }
-        ret
     }
 
-    /// Appends an element to the back of the vector.
-    ///
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after position `index` one position to the left.
+    ///
+    /// Unlike `remove`, an invalid `index` results in an error rather than a
+    /// panic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec.try_remove(1), Ok(2));
+    /// assert_eq!(
+    ///     vec.try_remove(2),
+    ///     Err(ErrorKind::IndexOutOfBounds { index: 2, len: 2 })
+    /// );
+    /// assert_eq!(vec.as_slice(), &[1, 3]);
+    /// # }
+    /// ```
+    pub fn try_remove(&mut self, index: usize) -> Result<T> {
+        if index >= self.len {
+            return Err(ErrorKind::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+        self.len -= 1;
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let ret = ptr::read(p);
+            ptr::copy(p.add(1), p, self.len - index);
+            Ok(ret)
+        }
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
     /// # Example
     ///
     /// ```
@@ -427,18 +1115,20 @@ where
     /// vec.push(3).unwrap();
     /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
     ///
-    /// // Attempting to push a full vector results in an error
-    /// assert!(vec.push(4).is_err());
+    /// // Attempting to push a full vector results in an error, which hands
+    /// // the element back
+    /// let err = vec.push(4).unwrap_err();
+    /// assert_eq!(err.into_inner(), 4);
     /// # }
     /// ```
     #[inline]
-    pub fn push(&mut self, value: T) -> Result<()> {
+    pub fn push(&mut self, value: T) -> core::result::Result<(), CapacityError<T>> {
         if self.available() >= 1 {
-            self.memory[self.len] = value;
+            self.memory[self.len] = MaybeUninit::new(value);
             self.len += 1;
             Ok(())
         } else {
-            Err(ErrorKind::NoSpace)
+            Err(CapacityError::new(value))
         }
     }
 
@@ -463,13 +1153,76 @@ where
     pub fn pop(&mut self) -> Option<T> {
         if self.len > 0 {
             self.len -= 1;
-            Some(self.memory[self.len])
+            // Read rather than replace, so that the slot is never overwritten
+            // with an uninitialized value.
+            Some(unsafe { ptr::read(self.memory[self.len].as_ptr()) })
         } else {
             None
         }
     }
 
-    /// Copies all elements from slice `other` to this vector.
+    /// Clears the vector, removing all values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec.len(), 3);
+    /// vec.clear();
+    /// assert_eq!(vec.len(), 0);
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+    /// vec.truncate(2);
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    ///
+    /// // Truncating to a greater length does nothing
+    /// vec.truncate(8);
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    /// # }
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = self.len - len;
+        // Shrink first, so that a panicking destructor cannot cause the same
+        // element to be dropped twice.
+        self.len = len;
+        unsafe {
+            let p = self.as_mut_ptr().add(len);
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(p, tail));
+        }
+    }
+
+    /// Moves all the elements of `other` to the end of this vector, leaving
+    /// `other` empty.
+    ///
+    /// If there is insufficient space for all of the elements, an error is
+    /// returned and NO elements are moved.
     ///
     /// # Example
     ///
@@ -479,30 +1232,47 @@ where
     /// # fn main() {
     /// let mut space = alloc_stack!([u8; 5]);
     /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2]).unwrap();
     ///
-    /// // All elements are pushed to vector
-    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
-    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// let mut other_space = alloc_stack!([u8; 5]);
+    /// let mut other = FixedVec::new(&mut other_space);
+    /// other.push_all(&[3, 4]).unwrap();
     ///
-    /// // If there is insufficient space, NO values are pushed
-    /// assert!(vec.push_all(&[5, 6, 7]).is_err());
+    /// vec.append(&mut other).unwrap();
     /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// assert!(other.is_empty());
+    ///
+    /// // Not enough room for both elements
+    /// other.push_all(&[5, 6]).unwrap();
+    /// assert!(vec.append(&mut other).is_err());
+    /// assert_eq!(other.as_slice(), &[5, 6]);
     /// # }
     /// ```
-    #[inline]
-    pub fn push_all(&mut self, other: &[T]) -> Result<()> {
-        if other.len() > self.available() {
-            Err(ErrorKind::NoSpace)
-        } else {
-            for item in other.iter() {
-                self.memory[self.len] = *item;
-                self.len += 1;
-            }
-            Ok(())
+    pub fn append(&mut self, other: &mut FixedVec<T>) -> Result<()> {
+        let count = other.len;
+        if count > self.available() {
+            return Err(ErrorKind::CapacityExceeded {
+                requested: count,
+                available: self.available(),
+            });
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(self.len), count);
         }
+        other.len = 0;
+        self.len += count;
+        Ok(())
     }
 
-    /// Clears the vector, removing all values.
+    /// Moves the elements in the range `[at, len)` to the end of `other`,
+    /// leaving the first `at` elements in this vector.
+    ///
+    /// If `other` does not have enough space for all of the elements, an error
+    /// is returned and NO elements are moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than the vector's length.
     ///
     /// # Example
     ///
@@ -510,16 +1280,77 @@ where
     /// # #[macro_use] extern crate fixedvec;
     /// # use fixedvec::FixedVec;
     /// # fn main() {
-    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut space = alloc_stack!([u8; 5]);
     /// let mut vec = FixedVec::new(&mut space);
-    /// vec.push_all(&[1, 2, 3]).unwrap();
-    /// assert_eq!(vec.len(), 3);
-    /// vec.clear();
-    /// assert_eq!(vec.len(), 0);
+    /// vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+    ///
+    /// let mut tail_space = alloc_stack!([u8; 2]);
+    /// let mut tail = FixedVec::new(&mut tail_space);
+    ///
+    /// // Too many elements for `tail`
+    /// assert!(vec.split_off_into(2, &mut tail).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4, 5]);
+    ///
+    /// vec.split_off_into(3, &mut tail).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// assert_eq!(tail.as_slice(), &[4, 5]);
     /// # }
     /// ```
-    pub fn clear(&mut self) {
-        self.len = 0
+    pub fn split_off_into(&mut self, at: usize, other: &mut FixedVec<T>) -> Result<()> {
+        assert!(at <= self.len);
+        let count = self.len - at;
+        if count > other.available() {
+            return Err(ErrorKind::CapacityExceeded {
+                requested: count,
+                available: other.available(),
+            });
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.as_ptr().add(at),
+                other.as_mut_ptr().add(other.len),
+                count,
+            );
+        }
+        self.len = at;
+        other.len += count;
+        Ok(())
+    }
+
+    /// Splits the vector's memory in two at its length, returning a vector
+    /// holding its elements and a new, empty vector using the spare capacity.
+    ///
+    /// The capacity of the first vector is its length. Both vectors can
+    /// shrink and grow independently, so one region can be shared between
+    /// several users. The elements move into the first vector, leaving the
+    /// original vector empty once both are gone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[3, 1, 2]).unwrap();
+    ///
+    /// let (mut elements, mut scratch) = vec.split_spare();
+    /// assert_eq!(elements.capacity(), 3);
+    /// assert_eq!(scratch.capacity(), 5);
+    /// scratch.push_all(&elements).unwrap();
+    /// scratch.sort_unstable();
+    /// elements.as_mut_slice().copy_from_slice(&scratch);
+    /// assert_eq!(elements.as_slice(), &[1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn split_spare(&mut self) -> (FixedVec<'_, T>, FixedVec<'_, T>) {
+        // The elements are owned by the first vector from here on.
+        let len = mem::replace(&mut self.len, 0);
+        let (head, tail) = self.memory.split_at_mut(len);
+        let mut elements = FixedVec::from_uninit(head);
+        elements.len = len;
+        (elements, FixedVec::from_uninit(tail))
     }
 
     /// Applies the function `f` to all elements in the vector, mutating the
@@ -543,8 +1374,8 @@ where
     where
         F: Fn(&mut T),
     {
-        for i in 0..self.len {
-            f(&mut self.memory[i]);
+        for x in self.as_mut_slice() {
+            f(x);
         }
     }
 
@@ -569,9 +1400,8 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<T> {
-        let (slice, _) = self.memory.split_at(self.len);
-        slice.iter()
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.as_slice().iter()
     }
 
     /// Provides a mutable forward iterator.
@@ -594,9 +1424,8 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        let (slice, _) = self.memory.split_at_mut(self.len);
-        slice.iter_mut()
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
     }
 
     /// Removes an element from anywhere in the vector and returns it,
@@ -623,49 +1452,51 @@ where
     /// # }
     /// ```
     pub fn swap_remove(&mut self, index: usize) -> T {
-        assert!(index < self.len);
-        if self.len == 1 {
-            self.remove(0)
-        } else {
-            let removed = self.memory[index];
-            self.memory[index] = self.pop().unwrap();
-            removed
+        match self.try_swap_remove(index) {
+            Ok(element) => element,
+            Err(err) => panic!("{}", err),
         }
     }
 
-    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
-    ///
-    /// New elements (if needed) are cloned from `value`.
-    ///
-    /// # Panics
+    /// Removes an element from anywhere in the vector and returns it,
+    /// replacing it with the last element.
     ///
-    /// Panics if `new_len` is greater than capacity
+    /// Unlike `swap_remove`, an invalid `index` results in an error rather
+    /// than a panic.
     ///
     /// # Example
     ///
     /// ```
     /// # #[macro_use] extern crate fixedvec;
-    /// # use fixedvec::FixedVec;
+    /// # use fixedvec::{ErrorKind, FixedVec};
     /// # fn main() {
     /// let mut space = alloc_stack!([u8; 10]);
     /// let mut vec = FixedVec::new(&mut space);
     ///
-    /// assert_eq!(vec.len(), 0);
-    /// vec.resize(5, 255);
-    /// assert_eq!(vec.as_slice(), &[255, 255, 255, 255, 255]);
-    /// vec.resize(2, 0);
-    /// assert_eq!(vec.as_slice(), &[255, 255]);
+    /// vec.push_all(&[0, 1, 2, 3]).unwrap();
+    /// assert_eq!(vec.try_swap_remove(1), Ok(1));
+    /// assert_eq!(
+    ///     vec.try_swap_remove(3),
+    ///     Err(ErrorKind::IndexOutOfBounds { index: 3, len: 3 })
+    /// );
+    /// assert_eq!(vec.as_slice(), &[0, 3, 2]);
     /// # }
     /// ```
-    pub fn resize(&mut self, new_len: usize, value: T) {
-        assert!(new_len <= self.capacity());
-        if new_len <= self.len {
-            self.len = new_len;
-        } else {
-            for i in self.memory[self.len..new_len].iter_mut() {
-                *i = Clone::clone(&value);
+    pub fn try_swap_remove(&mut self, index: usize) -> Result<T> {
+        if index >= self.len {
+            return Err(ErrorKind::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+        self.len -= 1;
+        unsafe {
+            let p = self.as_mut_ptr();
+            let ret = ptr::read(p.add(index));
+            if index != self.len {
+                ptr::copy_nonoverlapping(p.add(self.len), p.add(index), 1);
             }
-            self.len = new_len;
+            Ok(ret)
         }
     }
 
@@ -693,19 +1524,136 @@ where
     where
         F: Fn(&T) -> bool,
     {
-        let mut head: usize = 0;
-        let mut tail: usize = 0;
-        loop {
-            if head >= self.len {
-                break;
-            }
-            if f(&self.memory[head]) {
-                self.memory[tail] = self.memory[head];
-                tail += 1;
+        let len = self.len;
+        let mut deleted = 0;
+        {
+            let v = self.as_mut_slice();
+            for i in 0..len {
+                if !f(&v[i]) {
+                    deleted += 1;
+                } else if deleted > 0 {
+                    v.swap(i - deleted, i);
+                }
             }
-            head += 1;
         }
-        self.len = tail;
+        self.truncate(len - deleted);
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element
+    /// should be removed.
+    ///
+    /// If the closure returns `true`, the element is removed from the vector
+    /// and yielded by the iterator. If the closure returns `false`, the
+    /// element remains in the vector. Unlike `retain`, the closure is given a
+    /// mutable reference to each element, and the removed elements are handed
+    /// back to the caller.
+    ///
+    /// If the iterator is dropped before it is exhausted, the elements that
+    /// have not been visited yet remain in the vector. The order of the
+    /// retained elements is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+    /// let mut evens = vec.extract_if(|x| *x % 2 == 0);
+    /// assert_eq!(evens.next(), Some(2));
+    /// assert_eq!(evens.next(), Some(4));
+    /// assert_eq!(evens.next(), Some(6));
+    /// assert_eq!(evens.next(), None);
+    /// drop(evens);
+    /// assert_eq!(vec.as_slice(), &[1, 3, 5]);
+    /// # }
+    /// ```
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, 'a, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len;
+        // Elements are in flux while the iterator is alive. Should it be
+        // leaked, the vector simply appears empty.
+        self.len = 0;
+        ExtractIf {
+            vec: self,
+            idx: 0,
+            deleted: 0,
+            old_len,
+            filter,
+        }
+    }
+
+    /// Removes the specified range from the vector, returning the removed
+    /// elements as an iterator. Elements after the range are shifted down
+    /// once the iterator is dropped.
+    ///
+    /// If the iterator is dropped before it is exhausted, the remaining
+    /// elements of the range are dropped as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end
+    /// of the range is greater than the vector's length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+    /// {
+    ///     let mut drain = vec.drain(1..3);
+    ///     assert_eq!(drain.next(), Some(2));
+    ///     assert_eq!(drain.next(), Some(3));
+    ///     assert_eq!(drain.next(), None);
+    /// }
+    /// assert_eq!(vec.as_slice(), &[1, 4, 5]);
+    ///
+    /// // Draining the full range clears the vector
+    /// vec.drain(..);
+    /// assert!(vec.is_empty());
+    /// # }
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, 'a, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n
+                .checked_add(1)
+                .expect("attempted to index slice up to maximum usize"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n
+                .checked_add(1)
+                .expect("attempted to index slice up to maximum usize"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end);
+        assert!(end <= len);
+        // Hide the drained range and the tail until the iterator is dropped,
+        // so that leaking it cannot expose moved-out elements.
+        self.len = start;
+        Drain {
+            vec: self,
+            idx: start,
+            end,
+            tail_start: end,
+            tail_len: len - end,
+        }
     }
 
     /// Returns a reference to the element at the given index, or `None` if the
@@ -758,6 +1706,10 @@ where
     /// bounds checking. Note that the result of an invalid index is undefined,
     /// and may not panic.
     ///
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    ///
     /// # Example
     ///
     /// ```
@@ -782,6 +1734,10 @@ where
     /// doing bounds checking. Note that the result of an invalid index is
     /// undefined, and may not panic.
     ///
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    ///
     /// # Example
     ///
     /// ```
@@ -809,11 +1765,54 @@ where
 
 impl<'a, T> FixedVec<'a, T>
 where
-    T: 'a + Copy + PartialEq<T>,
+    T: 'a + Clone,
 {
-    /// Removes consecutive repeated elements in the vector in O(N) time.
+    /// Clones all elements from slice `other` to this vector.
     ///
-    /// If the vector is sorted, this removes all duplicates.
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// // All elements are pushed to vector
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    ///
+    /// // If there is insufficient space, NO values are pushed
+    /// assert_eq!(
+    ///     vec.push_all(&[5, 6, 7]),
+    ///     Err(ErrorKind::CapacityExceeded { requested: 3, available: 1 })
+    /// );
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push_all(&mut self, other: &[T]) -> Result<()> {
+        if other.len() > self.available() {
+            Err(ErrorKind::CapacityExceeded {
+                requested: other.len(),
+                available: self.available(),
+            })
+        } else {
+            for item in other.iter() {
+                self.memory[self.len] = MaybeUninit::new(item.clone());
+                self.len += 1;
+            }
+            Ok(())
+        }
+    }
+
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
+    ///
+    /// New elements (if needed) are cloned from `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than capacity
     ///
     /// # Example
     ///
@@ -823,121 +1822,524 @@ where
     /// # fn main() {
     /// let mut space = alloc_stack!([u8; 10]);
     /// let mut vec = FixedVec::new(&mut space);
-    /// vec.push_all(&[1, 2, 2, 3, 2]).unwrap();
-    /// vec.dedup();
-    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 2]);
+    ///
+    /// assert_eq!(vec.len(), 0);
+    /// vec.resize(5, 255);
+    /// assert_eq!(vec.as_slice(), &[255, 255, 255, 255, 255]);
+    /// vec.resize(2, 0);
+    /// assert_eq!(vec.as_slice(), &[255, 255]);
     /// # }
     /// ```
-    pub fn dedup(&mut self) {
-        if self.len <= 1 {
-            return;
-        }
-        let mut head: usize = 1;
-        let mut tail: usize = 0;
-        loop {
-            if head >= self.len {
-                break;
-            }
-            if self.memory[head] != self.memory[tail] {
-                tail += 1;
-                self.memory[tail] = self.memory[head];
-            }
-            head += 1;
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        if let Err(err) = self.try_resize(new_len, value) {
+            panic!("{}", err);
         }
-        self.len = tail + 1;
     }
-}
-
-impl<'a, T: Copy> IntoIterator for &'a FixedVec<'a, T> {
-    type Item = &'a T;
-    type IntoIter = Iter<'a, T>;
 
-    fn into_iter(self) -> Iter<'a, T> {
-        self.iter()
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
+    ///
+    /// New elements (if needed) are cloned from `value`. Unlike `resize`, a
+    /// `new_len` greater than the capacity results in an error rather than a
+    /// panic, and the vector is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.try_resize(2, 255).unwrap();
+    /// assert_eq!(
+    ///     vec.try_resize(5, 0),
+    ///     Err(ErrorKind::CapacityExceeded { requested: 3, available: 2 })
+    /// );
+    /// assert_eq!(vec.as_slice(), &[255, 255]);
+    /// # }
+    /// ```
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<()> {
+        if new_len > self.capacity() {
+            return Err(ErrorKind::CapacityExceeded {
+                requested: new_len - self.len,
+                available: self.available(),
+            });
+        }
+        if new_len <= self.len {
+            self.truncate(new_len);
+        } else {
+            unsafe {
+                let p = self.as_mut_ptr();
+                while self.len + 1 < new_len {
+                    ptr::write(p.add(self.len), value.clone());
+                    self.len += 1;
+                }
+                ptr::write(p.add(self.len), value);
+            }
+            self.len += 1;
+        }
+        Ok(())
     }
 }
 
-impl<'a, T: Copy> IntoIterator for &'a mut FixedVec<'a, T> {
-    type Item = &'a mut T;
-    type IntoIter = IterMut<'a, T>;
-
-    fn into_iter(self) -> IterMut<'a, T> {
+impl<'a, T> FixedVec<'a, T>
+where
+    T: 'a + PartialEq<T>,
+{
+    /// Removes consecutive repeated elements in the vector in O(N) time.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 2, 3, 2]).unwrap();
+    /// vec.dedup();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 2]);
+    /// # }
+    /// ```
+    pub fn dedup(&mut self) {
+        if self.len <= 1 {
+            return;
+        }
+        let len = self.len;
+        let mut write: usize = 1;
+        {
+            let v = self.as_mut_slice();
+            for read in 1..len {
+                if v[read] != v[write - 1] {
+                    v.swap(read, write);
+                    write += 1;
+                }
+            }
+        }
+        self.truncate(write);
+    }
+}
+
+impl<'a> FixedVec<'a, u8> {
+    /// Returns the contents of the vector as a string slice, if they are
+    /// valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// use std::fmt::Write;
+    ///
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// write!(vec, "{}-{}", 1, 2).unwrap();
+    /// assert_eq!(vec.as_str(), Ok("1-2"));
+    ///
+    /// vec.push(0xff).unwrap();
+    /// assert!(vec.as_str().is_err());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> core::result::Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self.as_slice())
+    }
+}
+
+/// A write-only view of the unused memory of a `FixedVec`, created by
+/// `FixedVec::spare_capacity_mut`.
+pub struct SpareCapacity<'b, T: 'b> {
+    slots: &'b mut [MaybeUninit<T>],
+}
+
+impl<'b, T> SpareCapacity<'b, T> {
+    /// Returns the number of slots.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if there are no slots.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Stores `value` in the slot at `index`, returning a reference to it.
+    ///
+    /// A value stored in the slot before is overwritten without being
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn write(&mut self, index: usize, value: T) -> &mut T {
+        self.slots[index] = MaybeUninit::new(value);
+        // The slot was just initialized.
+        unsafe { &mut *self.slots[index].as_mut_ptr() }
+    }
+
+    /// Returns a raw pointer to the first slot, for filling the slots by
+    /// other means, such as DMA.
+    ///
+    /// The pointer is valid for `len()` elements. Only initialized values may
+    /// be written through it.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.slots.as_mut_ptr() as *mut T
+    }
+}
+
+/// A draining iterator for `FixedVec`, created by `FixedVec::drain`.
+pub struct Drain<'b, 'a: 'b, T: 'a> {
+    vec: &'b mut FixedVec<'a, T>,
+    idx: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<'b, 'a, T> Iterator for Drain<'b, 'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.idx < self.end {
+            self.idx += 1;
+            Some(unsafe { ptr::read(self.vec.as_ptr().add(self.idx - 1)) })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'b, 'a, T> DoubleEndedIterator for Drain<'b, 'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.idx < self.end {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.vec.as_ptr().add(self.end)) })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'b, 'a, T> ExactSizeIterator for Drain<'b, 'a, T> {}
+
+impl<'b, 'a, T> Drop for Drain<'b, 'a, T> {
+    fn drop(&mut self) {
+        // Moves the tail back into place even if dropping one of the
+        // remaining elements panics.
+        struct TailGuard<'r, 'b: 'r, 'a: 'b, T: 'a>(&'r mut Drain<'b, 'a, T>);
+
+        impl<'r, 'b, 'a, T> Drop for TailGuard<'r, 'b, 'a, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let start = drain.vec.len;
+                unsafe {
+                    let p = drain.vec.as_mut_ptr();
+                    ptr::copy(p.add(drain.tail_start), p.add(start), drain.tail_len);
+                }
+                drain.vec.len = start + drain.tail_len;
+            }
+        }
+
+        let idx = self.idx;
+        let remaining = self.end - idx;
+        self.idx = self.end;
+        let guard = TailGuard(self);
+        unsafe {
+            let p = guard.0.vec.as_mut_ptr().add(idx);
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(p, remaining));
+        }
+    }
+}
+
+/// An iterator which removes the elements matching a predicate from a
+/// `FixedVec`, created by `FixedVec::extract_if`.
+pub struct ExtractIf<'b, 'a: 'b, T: 'a, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    vec: &'b mut FixedVec<'a, T>,
+    idx: usize,
+    deleted: usize,
+    old_len: usize,
+    filter: F,
+}
+
+impl<'b, 'a, T, F> Iterator for ExtractIf<'b, 'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while self.idx < self.old_len {
+                let p = self.vec.as_mut_ptr();
+                let extract = (self.filter)(&mut *p.add(self.idx));
+                self.idx += 1;
+                if extract {
+                    self.deleted += 1;
+                    return Some(ptr::read(p.add(self.idx - 1)));
+                } else if self.deleted > 0 {
+                    let src = p.add(self.idx - 1);
+                    ptr::copy_nonoverlapping(src, src.sub(self.deleted), 1);
+                }
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<'b, 'a, T, F> Drop for ExtractIf<'b, 'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        // Close the gap left by the extracted elements, keeping any that have
+        // not been visited.
+        unsafe {
+            if self.deleted > 0 && self.idx < self.old_len {
+                let src = self.vec.as_mut_ptr().add(self.idx);
+                ptr::copy(src, src.sub(self.deleted), self.old_len - self.idx);
+            }
+        }
+        self.vec.len = self.old_len - self.deleted;
+    }
+}
+
+impl<'a, T> Drop for FixedVec<'a, T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T> fmt::Debug for FixedVec<'a, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b FixedVec<'a, T> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b mut FixedVec<'a, T> {
+    type Item = &'b mut T;
+    type IntoIter = IterMut<'b, T>;
+
+    fn into_iter(self) -> IterMut<'b, T> {
         self.iter_mut()
     }
 }
 
 impl<'a, T> Hash for FixedVec<'a, T>
 where
-    T: Copy + Hash,
+    T: Hash,
 {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        Hash::hash(&*self.memory, state)
+        Hash::hash(self.as_slice(), state)
     }
 }
 
-impl<'a, T> Extend<T> for FixedVec<'a, T>
-where
-    T: Copy,
-{
+impl<'a, T> Extend<T> for FixedVec<'a, T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
-        if self.available() == 0 {
-            return;
-        }
-        for n in iterable {
-            self.memory[self.len] = n;
-            self.len += 1;
-            if self.available() == 0 {
-                break;
+        let mut iter = iterable.into_iter();
+        while self.available() > 0 {
+            match iter.next() {
+                Some(n) => {
+                    self.memory[self.len] = MaybeUninit::new(n);
+                    self.len += 1;
+                }
+                None => break,
             }
         }
     }
 }
 
-impl<'a, T> ops::Index<usize> for FixedVec<'a, T>
+/// Appends formatted text to the vector.
+///
+/// Each string is written in full or not at all: if it does not fit, nothing
+/// is written and `fmt::Error` is returned. Output from earlier pieces of the
+/// same `write!` call is kept.
+impl<'a> fmt::Write for FixedVec<'a, u8> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+/// Appends bytes to the vector.
+///
+/// `write` stores as many bytes as fit and returns how many that was, so
+/// `write_all` fails with `ErrorKind::WriteZero` once the vector is full.
+#[cfg(feature = "std")]
+impl<'a> std::io::Write for FixedVec<'a, u8> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let count = core::cmp::min(buf.len(), self.available());
+        self.push_all(&buf[..count])
+            .expect("count is limited to the available space");
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, T> ops::Deref for FixedVec<'a, T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T> ops::DerefMut for FixedVec<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T> AsRef<[T]> for FixedVec<'a, T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T> AsMut<[T]> for FixedVec<'a, T> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T> Borrow<[T]> for FixedVec<'a, T> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T> BorrowMut<[T]> for FixedVec<'a, T> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T, I> ops::Index<I> for FixedVec<'a, T>
 where
-    T: Copy,
+    I: SliceIndex<[T]>,
 {
-    type Output = T;
+    type Output = I::Output;
 
     #[inline]
-    fn index(&self, index: usize) -> &T {
-        &(self.memory)[index]
+    fn index(&self, index: I) -> &I::Output {
+        ops::Index::index(self.as_slice(), index)
     }
 }
 
-impl<'a, T> ops::IndexMut<usize> for FixedVec<'a, T>
+impl<'a, T, I> ops::IndexMut<I> for FixedVec<'a, T>
 where
-    T: Copy,
+    I: SliceIndex<[T]>,
 {
     #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut T {
-        &mut (self.memory)[index]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        ops::IndexMut::index_mut(self.as_mut_slice(), index)
     }
 }
 
-impl<'a, T> PartialEq for FixedVec<'a, T>
+impl_slice_eq! { ['a, 'b, A, B] FixedVec<'a, A>, FixedVec<'b, B> }
+impl_slice_eq! { ['a, A, B] FixedVec<'a, A>, [B] }
+impl_slice_eq! { ['a, 'b, A, B] FixedVec<'a, A>, &'b [B] }
+impl_slice_eq! { ['a, 'b, A, B] FixedVec<'a, A>, &'b mut [B] }
+impl_slice_eq! { ['a, A, B, const N: usize] FixedVec<'a, A>, [B; N] }
+impl_slice_eq! { ['a, 'b, A, B, const N: usize] FixedVec<'a, A>, &'b [B; N] }
+impl_slice_eq! { ['a, 'b, A, B, const N: usize] FixedVec<'a, A>, &'b mut [B; N] }
+impl_slice_eq! { ['a, A, B] [A], FixedVec<'a, B> }
+impl_slice_eq! { ['a, 'b, A, B] &'b [A], FixedVec<'a, B> }
+impl_slice_eq! { ['a, 'b, A, B] &'b mut [A], FixedVec<'a, B> }
+impl_slice_eq! { ['a, A, B, const N: usize] [A; N], FixedVec<'a, B> }
+#[cfg(feature = "alloc")]
+impl_slice_eq! { ['a, A, B] FixedVec<'a, A>, alloc::vec::Vec<B> }
+#[cfg(feature = "alloc")]
+impl_slice_eq! { ['a, A, B] alloc::vec::Vec<A>, FixedVec<'a, B> }
+
+impl<'a, T> Eq for FixedVec<'a, T> where T: Eq {}
+
+impl<'a, T> PartialOrd for FixedVec<'a, T>
 where
-    T: Copy + PartialEq,
+    T: PartialOrd,
 {
-    fn eq(&self, other: &FixedVec<'a, T>) -> bool {
-        if self.len() != other.len() {
-            return false;
-        }
-
-        (0..self.len()).all(|i| self[i] == other[i])
+    #[inline]
+    fn partial_cmp(&self, other: &FixedVec<'a, T>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(self.as_slice(), other.as_slice())
     }
 }
 
-impl<'a, T> Eq for FixedVec<'a, T> where T: Copy + Eq {}
+impl<'a, T> Ord for FixedVec<'a, T>
+where
+    T: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &FixedVec<'a, T>) -> Ordering {
+        Ord::cmp(self.as_slice(), other.as_slice())
+    }
+}
 
+// The original tests predate these lints.
 #[cfg(test)]
+#[allow(clippy::map_clone, clippy::unit_cmp, clippy::zero_repeat_side_effects)]
 mod test {
-    use super::FixedVec;
+    use super::{ErrorKind, FixedVec, Result};
+    use std::cell::Cell;
+    use std::cmp::Ordering;
     use std::collections::hash_map::DefaultHasher;
-    use std::hash::Hash;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+    use std::ops::Bound;
     use std::prelude::v1::*;
 
+    #[derive(Clone)]
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl<'a> Drop for DropCounter<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
     #[test]
     fn test_empty_array() {
         let mut empty = alloc_stack!([u8; 0]);
@@ -1003,6 +2405,12 @@ mod test {
         assert_eq!(vec.as_slice(), &result[..]);
     }
 
+    fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
     #[test]
     fn test_hash() {
         // Two vectors with the same contents should have the same hash
@@ -1017,6 +2425,92 @@ mod test {
         assert_eq!(vec1.hash(&mut hasher1), vec2.hash(&mut hasher2));
     }
 
+    #[test]
+    fn test_hash_ignores_stale_memory() {
+        // Memory beyond the length must not affect the hash
+        let mut space1 = [9u8; 10];
+        let mut vec1 = FixedVec::new(&mut space1);
+        vec1.push_all(&[1, 2, 3]).unwrap();
+        let mut space2 = [0u8; 5];
+        let mut vec2 = FixedVec::new(&mut space2);
+        vec2.push_all(&[1, 2, 3, 4]).unwrap();
+        vec2.pop();
+
+        assert_eq!(vec1, vec2);
+        assert_eq!(hash_of(&vec1), hash_of(&vec2));
+        assert_eq!(hash_of(&vec1), hash_of(&[1u8, 2, 3][..]));
+
+        // Vectors can be looked up by slice in hashed collections
+        let mut set = HashSet::new();
+        set.insert(vec1);
+        assert!(set.contains(&[1u8, 2, 3][..]));
+        assert!(!set.contains(&[1u8, 2][..]));
+    }
+
+    #[test]
+    fn test_cross_type_equal() {
+        let mut space1 = alloc_stack!([u8; 10]);
+        let mut vec1 = FixedVec::new(&mut space1);
+        vec1.push_all(&[1, 2, 3]).unwrap();
+
+        assert_eq!(vec1, [1, 2, 3]);
+        assert_eq!(vec1, &[1, 2, 3]);
+        assert_eq!(vec1, &[1, 2, 3][..]);
+        assert!(vec1 == [1, 2, 3][..]);
+        assert_eq!(vec1, &mut [1, 2, 3][..]);
+        assert_eq!(vec1, &mut [1, 2, 3]);
+        assert_eq!([1, 2, 3], vec1);
+        assert_eq!(&[1, 2, 3][..], vec1);
+        assert!(vec1 != [1, 2]);
+        assert!(vec1 != [1, 2, 4]);
+
+        // Element types only need to be comparable
+        let mut space2 = alloc_stack!([&str; 10]);
+        let mut vec2 = FixedVec::new(&mut space2);
+        vec2.push_all(&["a", "b"]).unwrap();
+        let strings = [String::from("a"), String::from("b")];
+        assert_eq!(vec2, strings);
+        assert_eq!(vec2, &strings[..]);
+
+        // Vectors over memory with different lifetimes
+        let vec3 = {
+            let mut space3 = alloc_stack!([u8; 3]);
+            let mut vec3 = FixedVec::new(&mut space3);
+            vec3.push_all(&[1, 2, 3]).unwrap();
+            vec3 == vec1
+        };
+        assert!(vec3);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_equal_vec() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        assert_eq!(vec, vec![1, 2, 3]);
+        assert_eq!(vec![1, 2, 3], vec);
+        assert!(vec != vec![1, 2]);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut space1 = alloc_stack!([u8; 10]);
+        let mut vec1 = FixedVec::new(&mut space1);
+        vec1.push_all(&[1, 2, 3]).unwrap();
+        let mut space2 = alloc_stack!([u8; 10]);
+        let mut vec2 = FixedVec::new(&mut space2);
+        vec2.push_all(&[1, 2]).unwrap();
+
+        assert!(vec2 < vec1);
+        vec2.push(4).unwrap();
+        assert!(vec2 > vec1);
+        assert_eq!(vec1.cmp(&vec2), Ordering::Less);
+        vec2.pop();
+        vec2.push(3).unwrap();
+        assert_eq!(vec1.cmp(&vec2), Ordering::Equal);
+    }
+
     #[test]
     fn test_extend() {
         let mut space = alloc_stack!([u8; 10]);
@@ -1038,4 +2532,409 @@ mod test {
 
         assert_eq!(vec1, vec2);
     }
+
+    #[test]
+    fn test_non_copy() {
+        let mut space = alloc_stack_uninit!([String; 4]);
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push(String::from("a")).unwrap();
+        vec.push(String::from("c")).unwrap();
+        vec.insert(1, String::from("b")).unwrap();
+        assert_eq!(vec.as_slice(), &["a", "b", "c"]);
+        assert_eq!(vec.remove(0), "a");
+        assert_eq!(vec.swap_remove(0), "b");
+        assert_eq!(vec.pop(), Some(String::from("c")));
+        assert_eq!(vec.pop(), None);
+    }
+
+    #[test]
+    fn test_drop_elements() {
+        let drops = Cell::new(0);
+        let mut space = alloc_stack_uninit!([DropCounter; 8]);
+        {
+            let mut vec = FixedVec::from_uninit(&mut space);
+            vec.extend((0..8).map(|_| DropCounter(&drops)));
+
+            drop(vec.pop());
+            assert_eq!(drops.get(), 1);
+
+            // Keep every other element
+            let seen = Cell::new(0);
+            vec.retain(|_| {
+                seen.set(seen.get() + 1);
+                seen.get() % 2 == 1
+            });
+            assert_eq!(vec.len(), 4);
+            assert_eq!(drops.get(), 4);
+
+            vec.resize(2, DropCounter(&drops));
+            // Two truncated elements plus the unused `value`
+            assert_eq!(drops.get(), 7);
+        }
+        // Remaining elements are dropped with the vector
+        assert_eq!(drops.get(), 9);
+    }
+
+    #[test]
+    fn test_drop_clear() {
+        let drops = Cell::new(0);
+        let mut space = alloc_stack_uninit!([DropCounter; 4]);
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.extend((0..3).map(|_| DropCounter(&drops)));
+        vec.clear();
+        assert_eq!(drops.get(), 3);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_initialized_bad_len() {
+        let mut space = alloc_stack!([u8; 4]);
+        FixedVec::from_initialized(&mut space, 5);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+        {
+            let mut drain = vec.drain(1..=4);
+            assert_eq!(drain.len(), 4);
+            assert_eq!(drain.next(), Some(2));
+            assert_eq!(drain.next_back(), Some(5));
+        }
+        assert_eq!(vec.as_slice(), &[1, 6]);
+
+        let drained: Vec<u8> = vec.drain(..1).collect();
+        assert_eq!(drained, vec![1]);
+        assert_eq!(vec.as_slice(), &[6]);
+
+        vec.drain(1..);
+        assert_eq!(vec.as_slice(), &[6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_bad_range() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.drain(2..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "maximum usize")]
+    fn test_drain_inclusive_end_overflow() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.drain(..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "maximum usize")]
+    fn test_drain_excluded_start_overflow() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.drain((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+
+    #[test]
+    fn test_drain_drops() {
+        let drops = Cell::new(0);
+        let mut space = alloc_stack_uninit!([DropCounter; 8]);
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.extend((0..8).map(|_| DropCounter(&drops)));
+        {
+            let mut drain = vec.drain(2..6);
+            drop(drain.next());
+            assert_eq!(drops.get(), 1);
+        }
+        // Undrained elements are dropped along with the iterator
+        assert_eq!(drops.get(), 4);
+        assert_eq!(vec.len(), 4);
+
+        // Leaking the iterator leaves only the elements before the range
+        std::mem::forget(vec.drain(1..3));
+        assert_eq!(vec.len(), 1);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        {
+            let mut evens = vec.extract_if(|x| *x % 2 == 0);
+            assert_eq!(evens.next(), Some(2));
+            assert_eq!(evens.next(), Some(4));
+        }
+        // Unvisited elements are kept when the iterator is dropped early
+        assert_eq!(vec.as_slice(), &[1, 3, 5, 6, 7, 8]);
+
+        let big: Vec<u8> = vec
+            .extract_if(|x| {
+                *x *= 10;
+                *x > 50
+            })
+            .collect();
+        assert_eq!(big, vec![60, 70, 80]);
+        assert_eq!(vec.as_slice(), &[10, 30, 50]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_into_bad_index() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        let mut other_space = alloc_stack!([u8; 10]);
+        let mut other = FixedVec::new(&mut other_space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.split_off_into(4, &mut other).unwrap();
+    }
+
+    #[test]
+    fn test_append_moves_ownership() {
+        let drops = Cell::new(0);
+        let mut space = alloc_stack_uninit!([DropCounter; 4]);
+        let mut other_space = alloc_stack_uninit!([DropCounter; 4]);
+        {
+            let mut vec = FixedVec::from_uninit(&mut space);
+            let mut other = FixedVec::from_uninit(&mut other_space);
+            other.extend((0..3).map(|_| DropCounter(&drops)));
+            vec.append(&mut other).unwrap();
+            assert_eq!(vec.len(), 3);
+
+            vec.split_off_into(1, &mut other).unwrap();
+            assert_eq!(other.len(), 2);
+            vec.truncate(0);
+            assert_eq!(drops.get(), 1);
+        }
+        // Each element is dropped exactly once
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_split_spare_drops_once() {
+        let drops = Cell::new(0);
+        let mut space = alloc_stack_uninit!([DropCounter; 6]);
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.extend((0..2).map(|_| DropCounter(&drops)));
+        {
+            let (elements, mut spare) = vec.split_spare();
+            assert_eq!(elements.len(), 2);
+            assert_eq!(elements.available(), 0);
+            spare.extend((0..4).map(|_| DropCounter(&drops)));
+            assert_eq!(spare.available(), 0);
+        }
+        // The elements went with the first vector
+        assert_eq!(drops.get(), 6);
+        assert!(vec.is_empty());
+        vec.push(DropCounter(&drops)).unwrap();
+        drop(vec);
+        assert_eq!(drops.get(), 7);
+    }
+
+    #[test]
+    fn test_spare_capacity_mut() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push(1).unwrap();
+        {
+            let mut spare = vec.spare_capacity_mut();
+            assert_eq!(spare.len(), 3);
+            spare.write(0, 2);
+            spare.write(1, 3);
+            *spare.write(2, 0) += 4;
+        }
+        assert_eq!(vec.as_slice(), &[1]);
+        unsafe { vec.set_len(4) };
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_errors() {
+        fn fill(vec: &mut FixedVec<u8>) -> Result<()> {
+            vec.push_all(&[1, 2])?;
+            vec.push(3)?;
+            Ok(())
+        }
+
+        let mut space = alloc_stack!([u8; 4]);
+        let mut vec = FixedVec::new(&mut space);
+        assert!(fill(&mut vec).is_ok());
+        assert_eq!(
+            fill(&mut vec),
+            Err(ErrorKind::CapacityExceeded {
+                requested: 2,
+                available: 1
+            })
+        );
+        vec.push(4).unwrap();
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+
+        let err = vec.push_all(&[5, 6]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "space needed for 2 elements, but only 0 available"
+        );
+        let err = vec.push(5).unwrap_err();
+        assert_eq!(err.to_string(), "no space left for another element");
+        assert_eq!(format!("{:?}", err), "CapacityError { kind: NoSpace }");
+    }
+
+    #[test]
+    fn test_try_bad_index() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+        let out_of_bounds = ErrorKind::IndexOutOfBounds { index: 8, len: 5 };
+        assert_eq!(vec.try_insert(8, 0).unwrap_err().kind(), out_of_bounds);
+        assert_eq!(vec.try_remove(8), Err(out_of_bounds));
+        assert_eq!(vec.try_swap_remove(8), Err(out_of_bounds));
+        assert_eq!(
+            vec.try_resize(15, 0),
+            Err(ErrorKind::CapacityExceeded {
+                requested: 10,
+                available: 5
+            })
+        );
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 8 is out of bounds for length 5")]
+    fn test_remove_bad_index_message() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+        vec.remove(8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_beyond_len() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        let _ = vec[5];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_mut_beyond_len() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec[3] = 4;
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_index_beyond_len() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        let _ = &vec[1..5];
+    }
+
+    #[test]
+    fn test_range_index() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(&vec[1..3], &[2, 3]);
+        assert_eq!(&vec[..], &[1, 2, 3, 4]);
+        assert_eq!(&vec[2..], &[3, 4]);
+        assert_eq!(&vec[..=1], &[1, 2]);
+
+        vec[1..3].copy_from_slice(&[7, 8]);
+        assert_eq!(vec.as_slice(), &[1, 7, 8, 4]);
+    }
+
+    #[test]
+    fn test_fmt_write() {
+        use core::fmt::Write;
+
+        let mut space = alloc_stack!([u8; 8]);
+        let mut vec = FixedVec::new(&mut space);
+        write!(vec, "ab{}", 12).unwrap();
+        assert_eq!(vec.as_str(), Ok("ab12"));
+
+        // The piece that does not fit is dropped entirely
+        let (first, second) = ("cd", "efg");
+        assert!(write!(vec, "{}{}", first, second).is_err());
+        assert_eq!(vec.as_str(), Ok("ab12cd"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_write() {
+        use std::io::{self, Write};
+
+        let mut space = alloc_stack!([u8; 4]);
+        let mut vec = FixedVec::new(&mut space);
+        assert_eq!(vec.write(b"abc").unwrap(), 3);
+        assert_eq!(vec.write(b"de").unwrap(), 1);
+        assert_eq!(vec.write(b"f").unwrap(), 0);
+        assert_eq!(vec.as_slice(), b"abcd");
+
+        vec.clear();
+        vec.write_all(b"xy").unwrap();
+        let err = vec.write_all(b"xyz").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_alloc_static() {
+        fn buffer() -> Option<FixedVec<'static, String>> {
+            alloc_static!(
+                #[cfg_attr(target_os = "linux", link_section = ".data.fixedvec_test")]
+                [String; 4]
+            )
+        }
+
+        let mut vec = buffer().unwrap();
+        vec.push(String::from("static")).unwrap();
+        assert_eq!(vec.capacity(), 4);
+        assert!(buffer().is_none());
+
+        // Each invocation of the macro has its own memory
+        let other: Option<FixedVec<'static, String>> = alloc_static!([String; 2]);
+        assert_eq!(other.unwrap().capacity(), 2);
+    }
+
+    #[test]
+    fn test_alloc_static_aligned() {
+        let vec: FixedVec<'static, u8> = alloc_static!(
+            #[cfg_attr(target_os = "linux", link_section = ".data.fixedvec_test")]
+            [u8; 3],
+            align = 64
+        )
+        .unwrap();
+        assert_eq!(vec.as_ptr() as usize % 64, 0);
+        assert_eq!(vec.capacity(), 3);
+    }
+
+    #[test]
+    fn test_deref() {
+        fn sum(values: &[u8]) -> u8 {
+            values.iter().sum()
+        }
+
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[3, 1, 2]).unwrap();
+        assert_eq!(sum(&vec), 6);
+        assert!(vec.contains(&2));
+        assert!(!vec.contains(&0));
+
+        vec.sort();
+        assert_eq!(vec.binary_search(&3), Ok(2));
+        assert_eq!(vec.iter().rev().cloned().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(vec.windows(2).count(), 2);
+        assert_eq!(AsRef::<[u8]>::as_ref(&vec), &[1, 2, 3]);
+    }
 }
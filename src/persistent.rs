@@ -0,0 +1,507 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A vector whose contents can be recovered after a reset.
+
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+use core::ops;
+use core::slice;
+
+use super::{CapacityError, ErrorKind, FixedVec, Iter, Result};
+
+/// Types that can be stored in a `PersistentVec`.
+///
+/// # Safety
+///
+/// Every bit pattern of the size of the type must be a valid value of it,
+/// and the type must not contain any padding bytes. This holds for the
+/// integer and floating point types and arrays of them, for which the trait
+/// is implemented. It can be implemented for `#[repr(C)]` structs made only
+/// of such fields, with no padding between them.
+pub unsafe trait Persistable: Copy {}
+
+macro_rules! impl_persistable {
+    ($($t:ty)*) => {
+        $(unsafe impl Persistable for $t {})*
+    };
+}
+
+impl_persistable! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64 }
+
+unsafe impl<T: Persistable, const N: usize> Persistable for [T; N] {}
+
+/// Identifies memory that holds a `PersistentVec`.
+const MAGIC: u32 = 0x4656_4543;
+
+#[repr(C)]
+struct Header {
+    magic: u32,
+    element_size: u32,
+    capacity: u32,
+    len: u32,
+    checksum: u32,
+}
+
+/// Computes the 32-bit FNV-1a hash of `bytes`, continuing from `hash`.
+fn fnv1a(mut hash: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+/// Returns the bytes of `elements`.
+fn as_bytes<T: Persistable>(elements: &[T]) -> &[u8] {
+    // `Persistable` types have no padding, so every byte is initialized.
+    unsafe { slice::from_raw_parts(elements.as_ptr() as *const u8, mem::size_of_val(elements)) }
+}
+
+/// A vector that keeps a header next to its elements, so that its contents
+/// can be recovered from memory that survived a reset.
+///
+/// The header holds a magic number, the element size, the capacity, the
+/// length, and an FNV-1a checksum of the elements, and is updated on every
+/// change. `recover` checks the header before trusting the memory, so memory
+/// that was never written, belongs to a different type, or was corrupted
+/// yields an empty vector instead of garbage.
+///
+/// The elements can be read like a slice. They are only changed through the
+/// methods of `PersistentVec`, so that the header always matches them; for
+/// changes beyond `push` and `pop`, use `update`.
+///
+/// A reset in the middle of a change can leave the header and elements out
+/// of step, in which case the contents are discarded on recovery.
+///
+/// # Example
+///
+/// ```
+/// use fixedvec::PersistentVec;
+///
+/// // Memory that is not cleared on a warm reset, for example a `static` in a
+/// // `.noinit` section
+/// let mut retained = [0u8; 64];
+///
+/// {
+///     let mut crumbs = PersistentVec::recover(&mut retained);
+///     assert!(crumbs.is_empty());
+///     crumbs.push(0xdead_u32).unwrap();
+///     crumbs.push(0xbeef_u32).unwrap();
+/// }
+///
+/// // After the reset
+/// let crumbs = PersistentVec::<u32>::recover(&mut retained);
+/// assert!(crumbs.was_recovered());
+/// assert_eq!(&crumbs[..], &[0xdead, 0xbeef]);
+/// ```
+pub struct PersistentVec<'a, T: 'a> {
+    header: &'a mut Header,
+    vec: FixedVec<'a, T>,
+    recovered: bool,
+}
+
+impl<'a, T> PersistentVec<'a, T>
+where
+    T: 'a + Persistable,
+{
+    /// Create a new, empty `PersistentVec` in the provided memory, discarding
+    /// anything stored there before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized, or if `memory` is too small to hold the
+    /// header.
+    pub fn new(memory: &'a mut [u8]) -> Self {
+        let mut vec = PersistentVec::split(memory);
+        vec.reset();
+        vec
+    }
+
+    /// Create a `PersistentVec` in the provided memory, keeping the elements
+    /// stored there before if the header is valid.
+    ///
+    /// If the header is not valid, the vector starts out empty.
+    /// `was_recovered` tells which happened.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized, or if `memory` is too small to hold the
+    /// header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fixedvec::PersistentVec;
+    ///
+    /// let mut memory = [0xffu8; 64];
+    /// let log = PersistentVec::<u16>::recover(&mut memory);
+    /// assert!(!log.was_recovered());
+    /// assert!(log.is_empty());
+    /// ```
+    pub fn recover(memory: &'a mut [u8]) -> Self {
+        let mut vec = PersistentVec::split(memory);
+        let header = &*vec.header;
+        let valid = header.magic == MAGIC
+            && header.element_size as usize == mem::size_of::<T>()
+            && header.capacity as usize == vec.vec.capacity()
+            && header.len <= header.capacity;
+        if valid {
+            // `Persistable` elements are valid whatever the memory holds.
+            unsafe { vec.vec.set_len(header.len as usize) };
+            if vec.checksum() == header.checksum {
+                vec.recovered = true;
+                return vec;
+            }
+        }
+        vec.reset();
+        vec
+    }
+
+    /// Divides `memory` into the header and the elements, without looking at
+    /// its contents.
+    fn split(memory: &'a mut [u8]) -> Self {
+        assert!(
+            mem::size_of::<T>() > 0,
+            "zero-sized types are not supported"
+        );
+        // `align_offset` may return `usize::MAX` if it cannot align the
+        // pointer, which the checked sums turn into a panic.
+        const TOO_SMALL: &str = "memory is too small for the header";
+        let header_start = memory.as_ptr().align_offset(mem::align_of::<Header>());
+        let header_end = header_start
+            .checked_add(mem::size_of::<Header>())
+            .expect(TOO_SMALL);
+        assert!(header_end <= memory.len(), "{}", TOO_SMALL);
+        let elements_start = memory[header_end..]
+            .as_ptr()
+            .align_offset(mem::align_of::<T>())
+            .checked_add(header_end)
+            .expect(TOO_SMALL);
+        let elements_start = core::cmp::min(elements_start, memory.len());
+
+        let (head, elements) = memory.split_at_mut(elements_start);
+        let capacity = core::cmp::min(elements.len() / mem::size_of::<T>(), u32::MAX as usize);
+        // The header and the elements are suitably aligned, every bit
+        // pattern is valid for the header, and elements are only read once
+        // the header vouches for them.
+        unsafe {
+            let header = &mut *(head.as_mut_ptr().add(header_start) as *mut Header);
+            let elements =
+                slice::from_raw_parts_mut(elements.as_mut_ptr() as *mut MaybeUninit<T>, capacity);
+            PersistentVec {
+                header,
+                vec: FixedVec::from_uninit(elements),
+                recovered: false,
+            }
+        }
+    }
+
+    /// Empties the vector and writes a fresh header.
+    fn reset(&mut self) {
+        self.vec.clear();
+        self.header.magic = MAGIC;
+        self.header.element_size = mem::size_of::<T>() as u32;
+        self.header.capacity = self.vec.capacity() as u32;
+        self.sync();
+    }
+
+    /// Computes the checksum of the header and the current elements.
+    fn checksum(&self) -> u32 {
+        let hash = fnv1a(0x811c_9dc5, &MAGIC.to_le_bytes());
+        let hash = fnv1a(hash, &self.header.element_size.to_le_bytes());
+        let hash = fnv1a(hash, &self.header.capacity.to_le_bytes());
+        fnv1a(hash, as_bytes(self.vec.as_slice()))
+    }
+
+    /// Updates the header to match the elements.
+    fn sync(&mut self) {
+        self.header.checksum = self.checksum();
+        self.header.len = self.vec.len() as u32;
+    }
+
+    /// Returns `true` if the contents were recovered from earlier use of the
+    /// memory by `recover`.
+    #[inline]
+    pub fn was_recovered(&self) -> bool {
+        self.recovered
+    }
+
+    /// Returns the capacity of the vector.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of elements that can be added before the vector is
+    /// full.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.vec.available()
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the elements of the vector as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    /// Returns an iterator over the elements of the vector.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.vec.iter()
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// If the vector is full, the element is handed back inside the error.
+    /// Only the new element is added to the checksum, so this takes time in
+    /// proportion to the size of one element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::PersistentVec;
+    /// let mut memory = [0u8; 32];
+    /// let mut vec = PersistentVec::new(&mut memory);
+    /// vec.push(1u32).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1]);
+    /// ```
+    pub fn push(&mut self, value: T) -> core::result::Result<(), CapacityError<T>> {
+        self.vec.push(value)?;
+        let len = self.vec.len();
+        self.header.checksum = fnv1a(self.header.checksum, as_bytes(&self.vec[len - 1..]));
+        self.header.len = len as u32;
+        Ok(())
+    }
+
+    /// Appends all elements of `other` to the back of the vector.
+    ///
+    /// If there is insufficient space for all of the elements, an error is
+    /// returned and NO elements are added.
+    pub fn push_all(&mut self, other: &[T]) -> Result<()> {
+        if other.len() > self.available() {
+            return Err(ErrorKind::CapacityExceeded {
+                requested: other.len(),
+                available: self.available(),
+            });
+        }
+        self.vec.push_all(other)?;
+        self.header.checksum = fnv1a(self.header.checksum, as_bytes(other));
+        self.header.len = self.vec.len() as u32;
+        Ok(())
+    }
+
+    /// Removes the last element from the vector and returns it, or `None` if
+    /// the vector is empty.
+    ///
+    /// The checksum is computed again, which takes time in proportion to the
+    /// length of the vector.
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.vec.pop();
+        if value.is_some() {
+            self.sync();
+        }
+        value
+    }
+
+    /// Shortens the vector, keeping the first `len` elements.
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+        self.sync();
+    }
+
+    /// Removes all elements from the vector.
+    pub fn clear(&mut self) {
+        self.vec.clear();
+        self.sync();
+    }
+
+    /// Calls `f` with a `FixedVec` over the vector's memory, for changes not
+    /// covered by the other methods, and then updates the header.
+    ///
+    /// If `f` replaces the `FixedVec` it is given with one over other memory,
+    /// the changes cannot be kept, and the vector is left empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::PersistentVec;
+    /// let mut memory = [0u8; 64];
+    /// {
+    ///     let mut vec = PersistentVec::new(&mut memory);
+    ///     vec.push_all(&[3u8, 1, 2]).unwrap();
+    ///     vec.update(|v| v.sort_unstable());
+    /// }
+    /// assert_eq!(PersistentVec::<u8>::recover(&mut memory).as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn update<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'r> FnOnce(&mut FixedVec<'r, T>) -> R,
+    {
+        // The temporary vector only borrows the memory for the duration of
+        // the call, so `f` cannot keep it, and it can be checked afterwards.
+        let base = self.vec.memory.as_ptr();
+        let capacity = self.vec.capacity();
+        let mut vec = FixedVec::from_uninit(&mut *self.vec.memory);
+        vec.len = self.vec.len;
+        let result = f(&mut vec);
+        let len = if vec.memory.as_ptr() == base && vec.capacity() == capacity {
+            // Elements are `Copy`, so dropping the temporary vector only
+            // forgets them.
+            vec.len
+        } else {
+            0
+        };
+        drop(vec);
+        // The first `len` elements were initialized by the temporary vector.
+        unsafe { self.vec.set_len(len) };
+        self.sync();
+        result
+    }
+}
+
+impl<'a, T> ops::Deref for PersistentVec<'a, T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+}
+
+impl<'a, T> AsRef<[T]> for PersistentVec<'a, T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+}
+
+impl<'a, T> fmt::Debug for PersistentVec<'a, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.vec, f)
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b PersistentVec<'a, T> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.vec.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PersistentVec;
+    use FixedVec;
+
+    #[test]
+    fn test_recover_after_changes() {
+        let mut memory = [0u8; 128];
+        {
+            let mut vec = PersistentVec::new(&mut memory);
+            vec.push_all(&[1u16, 2, 3, 4]).unwrap();
+            assert_eq!(vec.pop(), Some(4));
+            vec.push(5).unwrap();
+            vec.update(|v| v.remove(0));
+        }
+        let vec = PersistentVec::<u16>::recover(&mut memory);
+        assert!(vec.was_recovered());
+        assert_eq!(vec.as_slice(), &[2, 3, 5]);
+    }
+
+    #[test]
+    fn test_update_replacing_memory() {
+        let mut memory = [0u8; 64];
+        {
+            let mut vec = PersistentVec::new(&mut memory);
+            vec.push_all(&[1u8, 2, 3]).unwrap();
+            // Only memory borrowed for `'static` can outlive the call
+            vec.update(|v| *v = FixedVec::new(&mut []));
+            assert!(vec.is_empty());
+            vec.push(4).unwrap();
+        }
+        let vec = PersistentVec::<u8>::recover(&mut memory);
+        assert!(vec.was_recovered());
+        assert_eq!(vec.as_slice(), &[4]);
+    }
+
+    #[test]
+    fn test_reject_corrupted() {
+        let mut memory = [0u8; 128];
+        {
+            let mut vec = PersistentVec::new(&mut memory);
+            vec.push_all(&[1u32, 2, 3]).unwrap();
+        }
+        // Flip a bit in the last stored byte
+        let last = memory.iter().rposition(|&b| b == 3).unwrap();
+        memory[last] ^= 0x40;
+        let vec = PersistentVec::<u32>::recover(&mut memory);
+        assert!(!vec.was_recovered());
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_reject_other_type() {
+        let mut memory = [0u8; 128];
+        {
+            let mut vec = PersistentVec::new(&mut memory);
+            vec.push(7u32).unwrap();
+        }
+        assert!(!PersistentVec::<u16>::recover(&mut memory).was_recovered());
+        // Recovering as another type reset the memory
+        assert!(!PersistentVec::<u32>::recover(&mut memory).was_recovered());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut memory = [0u8; 64];
+        let vec = PersistentVec::<[u8; 3]>::new(&mut memory);
+        // The header takes up 20 bytes, plus up to 3 bytes of alignment
+        assert!(vec.capacity() == 14 || vec.capacity() == 13);
+        assert_eq!(vec.available(), vec.capacity());
+    }
+
+    #[test]
+    #[should_panic(expected = "too small")]
+    fn test_memory_too_small() {
+        let mut memory = [0u8; 8];
+        PersistentVec::<u8>::new(&mut memory);
+    }
+}
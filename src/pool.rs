@@ -0,0 +1,247 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A bump allocator handing out `FixedVec`s from one block of memory.
+
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+use core::slice;
+
+use super::{as_uninit_mut, ErrorKind, FixedVec, Result};
+
+/// A pool that carves `FixedVec`s of requested capacities out of one block of
+/// memory.
+///
+/// Allocation bumps an offset into the block, so it takes constant time and
+/// never fragments, and the pool's capacity is a hard budget for everything
+/// allocated from it. Memory is not reclaimed when a vector is dropped, only
+/// all at once by `reset`, which requires that no vectors from the pool are
+/// still alive.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedVecPool;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 16]);
+/// let mut pool = FixedVecPool::new(&mut space);
+///
+/// {
+///     let mut rx = pool.alloc(8).unwrap();
+///     let mut tx = pool.alloc(4).unwrap();
+///     rx.push_all(&[1, 2, 3]).unwrap();
+///     tx.push(4).unwrap();
+///     assert_eq!(pool.available(), 4);
+///
+///     // Over budget
+///     assert!(pool.alloc(5).is_err());
+/// }
+///
+/// pool.reset();
+/// assert_eq!(pool.available(), 16);
+/// # }
+/// ```
+pub struct FixedVecPool<'a, T: 'a> {
+    memory: &'a [UnsafeCell<MaybeUninit<T>>],
+    // Everything before `used` belongs to a vector handed out by `alloc`.
+    used: Cell<usize>,
+}
+
+// The pool hands out its memory, but never shares it.
+unsafe impl<'a, T> Send for FixedVecPool<'a, T> where T: Send {}
+
+impl<'a, T> FixedVecPool<'a, T>
+where
+    T: 'a + Copy,
+{
+    /// Create a new `FixedVecPool` from the provided slice, in the process
+    /// taking ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way, since the slice still holds
+    /// valid values once the pool is gone. Use `from_uninit` for other types.
+    pub fn new(memory: &'a mut [T]) -> Self {
+        FixedVecPool::from_uninit(as_uninit_mut(memory))
+    }
+}
+
+impl<'a, T> FixedVecPool<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new `FixedVecPool` from the provided slice of uninitialized
+    /// memory, in the process taking ownership of the slice.
+    ///
+    /// This works for any element type, including types that are not `Copy`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVecPool;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([String; 4]);
+    /// let pool = FixedVecPool::from_uninit(&mut space);
+    /// let mut names = pool.alloc(2).unwrap();
+    /// names.push(String::from("sensor")).unwrap();
+    /// assert_eq!(names[0], "sensor");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        // `UnsafeCell<U>` has the same layout as `U`, and the exclusive borrow
+        // guarantees nothing else accesses the memory.
+        let memory =
+            unsafe { &*(memory as *mut [MaybeUninit<T>] as *const [UnsafeCell<MaybeUninit<T>>]) };
+        FixedVecPool {
+            memory,
+            used: Cell::new(0),
+        }
+    }
+
+    /// Returns the total capacity of the pool.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Returns the capacity handed out since the pool was created or last
+    /// reset.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// Returns the capacity that can still be handed out.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.capacity() - self.used()
+    }
+
+    /// Returns a new, empty `FixedVec` with the given capacity, taken from
+    /// the pool.
+    ///
+    /// If the pool has less than `capacity` left, an error is returned and
+    /// nothing is taken.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedVecPool};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u32; 10]);
+    /// let pool = FixedVecPool::new(&mut space);
+    /// let vec = pool.alloc(6).unwrap();
+    /// assert_eq!(vec.capacity(), 6);
+    /// assert_eq!(
+    ///     pool.alloc(6).unwrap_err(),
+    ///     ErrorKind::CapacityExceeded { requested: 6, available: 4 }
+    /// );
+    /// # }
+    /// ```
+    pub fn alloc(&self, capacity: usize) -> Result<FixedVec<'_, T>> {
+        let start = self.used.get();
+        if capacity > self.available() {
+            return Err(ErrorKind::CapacityExceeded {
+                requested: capacity,
+                available: self.available(),
+            });
+        }
+        self.used.set(start + capacity);
+        // The range was never handed out before, and stays out of reach of
+        // other vectors until `reset`, which cannot be called while this one
+        // borrows the pool.
+        let memory = unsafe {
+            slice::from_raw_parts_mut(
+                self.memory[start..].as_ptr() as *mut MaybeUninit<T>,
+                capacity,
+            )
+        };
+        Ok(FixedVec::from_uninit(memory))
+    }
+
+    /// Returns a new, empty `FixedVec` using all of the memory left in the
+    /// pool.
+    pub fn alloc_rest(&self) -> FixedVec<'_, T> {
+        self.alloc(self.available())
+            .expect("the remaining memory is always available")
+    }
+
+    /// Reclaims all memory handed out by the pool.
+    ///
+    /// The borrow checker ensures that every vector allocated from the pool
+    /// has been dropped first.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.used.set(0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedVecPool;
+    use std::cell::Cell;
+    use std::prelude::v1::*;
+
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl<'a> Drop for DropCounter<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_vectors_are_disjoint() {
+        let mut space = alloc_stack!([u8; 8]);
+        let pool = FixedVecPool::new(&mut space);
+        let mut a = pool.alloc(3).unwrap();
+        let mut b = pool.alloc(0).unwrap();
+        let mut c = pool.alloc_rest();
+        assert_eq!(c.capacity(), 5);
+        assert_eq!(pool.available(), 0);
+
+        a.resize(3, 1);
+        assert!(b.push(2).is_err());
+        c.resize(5, 3);
+        assert_eq!(a.as_slice(), &[1, 1, 1]);
+        assert_eq!(c.as_slice(), &[3, 3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_reset() {
+        let drops = Cell::new(0);
+        let mut space = alloc_stack_uninit!([DropCounter; 4]);
+        let mut pool = FixedVecPool::from_uninit(&mut space);
+        for _ in 0..3 {
+            {
+                let mut vec = pool.alloc(4).unwrap();
+                vec.extend((0..4).map(|_| DropCounter(&drops)));
+                assert!(pool.alloc(1).is_err());
+            }
+            assert_eq!(pool.used(), 4);
+            pool.reset();
+            assert_eq!(pool.used(), 0);
+        }
+        assert_eq!(drops.get(), 12);
+    }
+}
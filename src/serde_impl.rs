@@ -0,0 +1,207 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Support for serializing and deserializing with `serde`.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use super::{FixedArrayVec, FixedVec};
+
+impl<'a, T> Serialize for FixedVec<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<T, const N: usize> Serialize for FixedArrayVec<T, N>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserializes a sequence into an existing `FixedVec`.
+///
+/// Any previous contents of the vector are dropped first. If the sequence has
+/// more elements than the vector's capacity, deserialization fails with an
+/// "invalid length" error, and the vector holds the elements that fit.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # extern crate serde;
+/// use fixedvec::{FixedVec, FixedVecSeed};
+/// use serde::de::value::{Error, SeqDeserializer};
+/// use serde::de::DeserializeSeed;
+///
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 4]);
+/// let mut vec = FixedVec::new(&mut space);
+/// vec.push(9).unwrap();
+///
+/// let de = SeqDeserializer::<_, Error>::new(vec![1u8, 2].into_iter());
+/// FixedVecSeed::new(&mut vec).deserialize(de).unwrap();
+/// assert_eq!(vec.as_slice(), &[1, 2]);
+/// # }
+/// ```
+pub struct FixedVecSeed<'r, 'a: 'r, T: 'a> {
+    vec: &'r mut FixedVec<'a, T>,
+}
+
+impl<'r, 'a, T> FixedVecSeed<'r, 'a, T> {
+    /// Create a seed that deserializes into `vec`.
+    pub fn new(vec: &'r mut FixedVec<'a, T>) -> Self {
+        FixedVecSeed { vec }
+    }
+}
+
+impl<'de, 'r, 'a, T> DeserializeSeed<'de> for FixedVecSeed<'r, 'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.vec.clear();
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'r, 'a, T> Visitor<'de> for FixedVecSeed<'r, 'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of at most {} elements", self.vec.capacity())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(element) = seq.next_element()? {
+            if self.vec.push(element).is_err() {
+                let len = self.vec.len() + 1 + seq.size_hint().unwrap_or(0);
+                return Err(de::Error::invalid_length(len, &self));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for FixedArrayVec<T, N>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ArrayVecVisitor<T, const N: usize>(PhantomData<T>);
+
+        impl<'de, T, const N: usize> Visitor<'de> for ArrayVecVisitor<T, N>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = FixedArrayVec<T, N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a sequence of at most {} elements", N)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                let mut vec = FixedArrayVec::new();
+                FixedVecSeed::new(&mut vec.as_fixed_vec()).visit_seq(seq)?;
+                Ok(vec)
+            }
+        }
+
+        deserializer.deserialize_seq(ArrayVecVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedVecSeed;
+    use serde::de::value::{Error, SeqDeserializer};
+    use serde::de::DeserializeSeed;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
+    use std::prelude::v1::*;
+    use {FixedArrayVec, FixedVec};
+
+    #[test]
+    fn test_serialize() {
+        let mut space = alloc_stack!([u16; 8]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2]).unwrap();
+        assert_ser_tokens(
+            &vec,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U16(1),
+                Token::U16(2),
+                Token::SeqEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_deserialize_seed_too_long() {
+        let mut space = alloc_stack!([u8; 2]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push(9).unwrap();
+
+        let de = SeqDeserializer::<_, Error>::new(vec![1u8, 2, 3].into_iter());
+        let err = FixedVecSeed::new(&mut vec).deserialize(de).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 3, expected a sequence of at most 2 elements"
+        );
+        assert_eq!(vec.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_array_vec_round_trip() {
+        let mut vec: FixedArrayVec<u8, 4> = FixedArrayVec::new();
+        vec.push_all(&[1, 2, 3]).unwrap();
+        let tokens = [
+            Token::Seq { len: Some(3) },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::SeqEnd,
+        ];
+        assert_ser_tokens(&vec, &tokens);
+        assert_de_tokens(&vec, &tokens);
+
+        assert_de_tokens_error::<FixedArrayVec<u8, 2>>(
+            &tokens,
+            "invalid length 3, expected a sequence of at most 2 elements",
+        );
+    }
+}
@@ -0,0 +1,976 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Sets and maps with fixed capacity, kept as sorted `FixedVec`s.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::mem;
+use core::mem::MaybeUninit;
+use core::ops::{Bound, RangeBounds};
+use core::slice;
+
+use super::{CapacityError, FixedVec, Iter};
+
+/// Returns the positions in `slice`, which is sorted by `key`, that the
+/// elements in `range` lie between.
+fn range_positions<T, Q, R, F>(slice: &[T], range: R, key: F) -> (usize, usize)
+where
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+    F: Fn(&T) -> &Q,
+{
+    let start = match range.start_bound() {
+        Bound::Included(b) => slice.partition_point(|x| key(x) < b),
+        Bound::Excluded(b) => slice.partition_point(|x| key(x) <= b),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(b) => slice.partition_point(|x| key(x) <= b),
+        Bound::Excluded(b) => slice.partition_point(|x| key(x) < b),
+        Bound::Unbounded => slice.len(),
+    };
+    (start, core::cmp::max(start, end))
+}
+
+/// A set of ordered elements, stored as a sorted `FixedVec`.
+///
+/// Lookups take O(log n) time, using binary search. Insertions and removals
+/// shift the elements after the affected position, so they take O(n) time,
+/// which is fast for the small tables this is meant for.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedSortedSet;
+/// # fn main() {
+/// let mut space = alloc_stack!([u16; 8]);
+/// let mut subscribers = FixedSortedSet::new(&mut space);
+///
+/// assert!(subscribers.insert(30).unwrap());
+/// assert!(subscribers.insert(10).unwrap());
+/// assert!(!subscribers.insert(30).unwrap());
+/// assert!(subscribers.contains(&10));
+/// assert_eq!(subscribers.as_slice(), &[10, 30]);
+/// # }
+/// ```
+pub struct FixedSortedSet<'a, T: 'a> {
+    vec: FixedVec<'a, T>,
+}
+
+impl<'a, T> FixedSortedSet<'a, T>
+where
+    T: 'a + Ord + Copy,
+{
+    /// Create a new, empty set from the provided slice, in the process
+    /// taking ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way; see `FixedVec::new`.
+    pub fn new(memory: &'a mut [T]) -> Self {
+        FixedSortedSet {
+            vec: FixedVec::new(memory),
+        }
+    }
+}
+
+impl<'a, T> FixedSortedSet<'a, T>
+where
+    T: 'a + Ord,
+{
+    /// Create a new, empty set from the provided slice of uninitialized
+    /// memory, in the process taking ownership of the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedSet;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([String; 4]);
+    /// let mut names = FixedSortedSet::from_uninit(&mut space);
+    /// names.insert(String::from("uart")).unwrap();
+    /// assert!(names.contains("uart"));
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedSortedSet {
+            vec: FixedVec::from_uninit(memory),
+        }
+    }
+
+    /// Turns a `FixedVec` into a set, sorting its elements and removing
+    /// duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedSortedSet, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[3, 1, 3, 2, 1]).unwrap();
+    ///
+    /// let set = FixedSortedSet::from_vec(vec);
+    /// assert_eq!(set.as_slice(), &[1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn from_vec(mut vec: FixedVec<'a, T>) -> Self {
+        vec.sort_unstable();
+        vec.dedup();
+        FixedSortedSet { vec }
+    }
+
+    /// Returns the capacity of the set.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of elements in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of elements that can be added before the set is
+    /// full.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.vec.available()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the elements of the set as a sorted slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    /// Returns an iterator over the elements of the set, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.vec.iter()
+    }
+
+    /// Returns the underlying vector, with the elements in ascending order.
+    #[inline]
+    pub fn into_vec(self) -> FixedVec<'a, T> {
+        self.vec
+    }
+
+    /// Removes all elements from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    fn search<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.binary_search_by(|x| x.borrow().cmp(value))
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns whether the value was newly inserted. If the set is full, the
+    /// value is handed back inside the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedSet;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut set = FixedSortedSet::new(&mut space);
+    /// assert!(set.insert(2).unwrap());
+    /// assert!(set.insert(1).unwrap());
+    /// // Inserting an element that is already present needs no space
+    /// assert!(!set.insert(2).unwrap());
+    /// assert_eq!(set.insert(3).unwrap_err().into_inner(), 3);
+    /// # }
+    /// ```
+    pub fn insert(&mut self, value: T) -> Result<bool, CapacityError<T>> {
+        match self.search(&value) {
+            Ok(_) => Ok(false),
+            Err(pos) => self.vec.insert(pos, value).map(|()| true),
+        }
+    }
+
+    /// Removes a value from the set, returning whether it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedSet;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut set = FixedSortedSet::new(&mut space);
+    /// set.insert(1).unwrap();
+    /// assert!(set.remove(&1));
+    /// assert!(!set.remove(&1));
+    /// # }
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Removes a value from the set and returns it, if it was present.
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(value) {
+            Ok(pos) => Some(self.vec.remove(pos)),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns `true` if the set contains the value.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(value).is_ok()
+    }
+
+    /// Returns a reference to the element in the set equal to the value, if
+    /// there is one.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(value) {
+            Ok(pos) => Some(&self.vec[pos]),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns an iterator over the elements of the set within `range`, in
+    /// ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedSet;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut set = FixedSortedSet::new(&mut space);
+    /// set.extend([5, 1, 9, 3, 7].iter().cloned());
+    ///
+    /// assert!(set.range(3..8).eq(&[3, 5, 7]));
+    /// assert!(set.range(..=3).eq(&[1, 3]));
+    /// # }
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = range_positions(self.as_slice(), range, |x| x.borrow());
+        self.as_slice()[start..end].iter()
+    }
+}
+
+impl<'a, T> fmt::Debug for FixedSortedSet<'a, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.vec.iter()).finish()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b FixedSortedSet<'a, T> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.vec.iter()
+    }
+}
+
+// Stops at the first new element that does not fit.
+impl<'a, T> Extend<T> for FixedSortedSet<'a, T>
+where
+    T: Ord,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        for value in iterable {
+            if self.insert(value).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a, 'b, T> PartialEq<FixedSortedSet<'b, T>> for FixedSortedSet<'a, T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &FixedSortedSet<'b, T>) -> bool {
+        self.vec == other.vec
+    }
+}
+
+impl<'a, T> Eq for FixedSortedSet<'a, T> where T: Eq {}
+
+/// A map from ordered keys to values, stored as a `FixedVec` of key-value
+/// pairs sorted by key.
+///
+/// As with `FixedSortedSet`, lookups take O(log n) time and insertions and
+/// removals take O(n) time.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedSortedMap;
+/// # fn main() {
+/// let mut space = alloc_stack!([(u8, u32); 8]);
+/// let mut registers = FixedSortedMap::new(&mut space);
+///
+/// registers.insert(0x10, 0xff).unwrap();
+/// registers.insert(0x04, 0x01).unwrap();
+/// assert_eq!(registers.get(&0x10), Some(&0xff));
+///
+/// *registers.entry(0x04).or_insert(0).unwrap() |= 0x80;
+/// assert_eq!(registers.as_slice(), &[(0x04, 0x81), (0x10, 0xff)]);
+/// # }
+/// ```
+pub struct FixedSortedMap<'a, K: 'a, V: 'a> {
+    vec: FixedVec<'a, (K, V)>,
+}
+
+impl<'a, K, V> FixedSortedMap<'a, K, V>
+where
+    K: 'a + Ord + Copy,
+    V: 'a + Copy,
+{
+    /// Create a new, empty map from the provided slice, in the process taking
+    /// ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way; see `FixedVec::new`.
+    pub fn new(memory: &'a mut [(K, V)]) -> Self {
+        FixedSortedMap {
+            vec: FixedVec::new(memory),
+        }
+    }
+}
+
+impl<'a, K, V> FixedSortedMap<'a, K, V>
+where
+    K: 'a + Ord,
+    V: 'a,
+{
+    /// Create a new, empty map from the provided slice of uninitialized
+    /// memory, in the process taking ownership of the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([(&str, String); 4]);
+    /// let mut map = FixedSortedMap::from_uninit(&mut space);
+    /// map.insert("greeting", String::from("hello")).unwrap();
+    /// assert_eq!(map["greeting"], "hello");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<(K, V)>]) -> Self {
+        FixedSortedMap {
+            vec: FixedVec::from_uninit(memory),
+        }
+    }
+
+    /// Returns the capacity of the map.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of entries that can be added before the map is
+    /// full.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.vec.available()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the entries of the map as a slice, sorted by key.
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.vec.as_slice()
+    }
+
+    /// Returns the underlying vector, with the entries sorted by key.
+    #[inline]
+    pub fn into_vec(self) -> FixedVec<'a, (K, V)> {
+        self.vec
+    }
+
+    /// Removes all entries from the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.binary_search_by(|entry| entry.0.borrow().cmp(key))
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the key was already present, its value is replaced and the old
+    /// value returned. If the map is full, the pair is handed back inside the
+    /// error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 1]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// assert_eq!(map.insert(1, 10).unwrap(), None);
+    /// assert_eq!(map.insert(1, 11).unwrap(), Some(10));
+    /// assert_eq!(map.insert(2, 20).unwrap_err().into_inner(), (2, 20));
+    /// # }
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CapacityError<(K, V)>> {
+        match self.search(&key) {
+            Ok(pos) => Ok(Some(mem::replace(&mut self.vec[pos].1, value))),
+            Err(pos) => self.vec.insert(pos, (key, value)).map(|()| None),
+        }
+    }
+
+    /// Removes a key from the map, returning its value if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 4]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.insert(1, 10).unwrap();
+    /// assert_eq!(map.remove(&1), Some(10));
+    /// assert_eq!(map.remove(&1), None);
+    /// # }
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and its value if
+    /// it was present.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(pos) => Some(self.vec.remove(pos)),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a reference to the value for the key, if there is one.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(pos) => Some(&self.vec[pos].1),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for the key, if there is
+    /// one.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(pos) => Some(&mut self.vec[pos].1),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// Returns the entry for the key, for in-place updates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(char, u32); 8]);
+    /// let mut counts = FixedSortedMap::new(&mut space);
+    /// for ch in "hello".chars() {
+    ///     *counts.entry(ch).or_insert(0).unwrap() += 1;
+    /// }
+    /// assert_eq!(counts.get(&'l'), Some(&2));
+    /// assert_eq!(counts.len(), 4);
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, 'a, K, V> {
+        match self.search(&key) {
+            Ok(pos) => Entry::Occupied(OccupiedEntry { map: self, pos }),
+            Err(pos) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                pos,
+            }),
+        }
+    }
+
+    /// Returns an iterator over the entries of the map, sorted by key.
+    #[inline]
+    pub fn iter(&self) -> MapIter<'_, K, V> {
+        MapIter {
+            inner: self.vec.iter(),
+        }
+    }
+
+    /// Returns an iterator over the entries of the map, sorted by key, with
+    /// mutable references to the values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 4]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.insert(2, 20).unwrap();
+    /// map.insert(1, 10).unwrap();
+    /// for (_, value) in map.iter_mut() {
+    ///     *value += 1;
+    /// }
+    /// assert!(map.iter().eq(vec![(&1, &11), (&2, &21)]));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> MapIterMut<'_, K, V> {
+        MapIterMut {
+            inner: self.vec.iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over the entries of the map with keys within
+    /// `range`, sorted by key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, char); 8]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.extend(vec![(1, 'a'), (3, 'c'), (5, 'e')]);
+    /// assert!(map.range(2..).eq(vec![(&3, &'c'), (&5, &'e')]));
+    /// # }
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> MapIter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = range_positions(self.as_slice(), range, |entry| entry.0.borrow());
+        MapIter {
+            inner: self.as_slice()[start..end].iter(),
+        }
+    }
+}
+
+/// An entry in a `FixedSortedMap`, created by `FixedSortedMap::entry`.
+pub enum Entry<'b, 'a: 'b, K: 'a, V: 'a> {
+    /// The key is present in the map.
+    Occupied(OccupiedEntry<'b, 'a, K, V>),
+    /// The key is not present in the map.
+    Vacant(VacantEntry<'b, 'a, K, V>),
+}
+
+impl<'b, 'a, K, V> Entry<'b, 'a, K, V>
+where
+    K: Ord,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            Entry::Occupied(ref entry) => entry.key(),
+            Entry::Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// Returns a mutable reference to the value, inserting `default` first
+    /// if the key is not present.
+    ///
+    /// If the map is full, the pair is handed back inside the error.
+    pub fn or_insert(self, default: V) -> Result<&'b mut V, CapacityError<(K, V)>> {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Returns a mutable reference to the value, inserting the result of
+    /// `default` first if the key is not present.
+    ///
+    /// If the map is full, the pair is handed back inside the error.
+    pub fn or_insert_with<F>(self, default: F) -> Result<&'b mut V, CapacityError<(K, V)>>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the value if the key is present, and returns the entry.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+/// An entry for a key that is present in a `FixedSortedMap`.
+pub struct OccupiedEntry<'b, 'a: 'b, K: 'a, V: 'a> {
+    map: &'b mut FixedSortedMap<'a, K, V>,
+    pos: usize,
+}
+
+impl<'b, 'a, K, V> OccupiedEntry<'b, 'a, K, V>
+where
+    K: Ord,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.map.vec[self.pos].0
+    }
+
+    /// Returns a reference to the value of the entry.
+    pub fn get(&self) -> &V {
+        &self.map.vec[self.pos].1
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.vec[self.pos].1
+    }
+
+    /// Returns a mutable reference to the value of the entry, with the
+    /// lifetime of the map.
+    pub fn into_mut(self) -> &'b mut V {
+        &mut self.map.vec[self.pos].1
+    }
+
+    /// Replaces the value of the entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, returning its value.
+    pub fn remove(self) -> V {
+        self.map.vec.remove(self.pos).1
+    }
+}
+
+/// An entry for a key that is not present in a `FixedSortedMap`.
+pub struct VacantEntry<'b, 'a: 'b, K: 'a, V: 'a> {
+    map: &'b mut FixedSortedMap<'a, K, V>,
+    key: K,
+    pos: usize,
+}
+
+impl<'b, 'a, K, V> VacantEntry<'b, 'a, K, V>
+where
+    K: Ord,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the key of the entry, consuming it.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the key with `value`, returning a mutable reference to the
+    /// value.
+    ///
+    /// If the map is full, the pair is handed back inside the error.
+    pub fn insert(self, value: V) -> Result<&'b mut V, CapacityError<(K, V)>> {
+        let pos = self.pos;
+        self.map.vec.insert(pos, (self.key, value))?;
+        Ok(&mut self.map.vec[pos].1)
+    }
+}
+
+/// An iterator over the entries of a `FixedSortedMap`, created by
+/// `FixedSortedMap::iter` and `FixedSortedMap::range`.
+pub struct MapIter<'b, K: 'b, V: 'b> {
+    inner: slice::Iter<'b, (K, V)>,
+}
+
+impl<'b, K, V> Iterator for MapIter<'b, K, V> {
+    type Item = (&'b K, &'b V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'b K, &'b V)> {
+        self.inner.next().map(|entry| (&entry.0, &entry.1))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, K, V> DoubleEndedIterator for MapIter<'b, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'b K, &'b V)> {
+        self.inner.next_back().map(|entry| (&entry.0, &entry.1))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for MapIter<'b, K, V> {}
+
+/// An iterator over the entries of a `FixedSortedMap` with mutable
+/// references to the values, created by `FixedSortedMap::iter_mut`.
+pub struct MapIterMut<'b, K: 'b, V: 'b> {
+    inner: slice::IterMut<'b, (K, V)>,
+}
+
+impl<'b, K, V> Iterator for MapIterMut<'b, K, V> {
+    type Item = (&'b K, &'b mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'b K, &'b mut V)> {
+        self.inner.next().map(|entry| (&entry.0, &mut entry.1))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, K, V> DoubleEndedIterator for MapIterMut<'b, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'b K, &'b mut V)> {
+        self.inner.next_back().map(|entry| (&entry.0, &mut entry.1))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for MapIterMut<'b, K, V> {}
+
+impl<'a, K, V> fmt::Debug for FixedSortedMap<'a, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.vec.iter().map(|entry| (&entry.0, &entry.1)))
+            .finish()
+    }
+}
+
+impl<'a, 'b, K, V> IntoIterator for &'b FixedSortedMap<'a, K, V>
+where
+    K: Ord,
+{
+    type Item = (&'b K, &'b V);
+    type IntoIter = MapIter<'b, K, V>;
+
+    fn into_iter(self) -> MapIter<'b, K, V> {
+        self.iter()
+    }
+}
+
+// Stops at the first new key that does not fit.
+impl<'a, K, V> Extend<(K, V)> for FixedSortedMap<'a, K, V>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        for (key, value) in iterable {
+            if self.insert(key, value).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a, 'q, K, V, Q> core::ops::Index<&'q Q> for FixedSortedMap<'a, K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value for the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<'a, 'b, K, V> PartialEq<FixedSortedMap<'b, K, V>> for FixedSortedMap<'a, K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &FixedSortedMap<'b, K, V>) -> bool {
+        self.vec == other.vec
+    }
+}
+
+impl<'a, K, V> Eq for FixedSortedMap<'a, K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Entry, FixedSortedMap, FixedSortedSet};
+    use std::ops::Bound::Excluded;
+    use std::prelude::v1::*;
+    use ErrorKind;
+
+    #[test]
+    fn test_set_full() {
+        let mut space = alloc_stack!([i32; 3]);
+        let mut set = FixedSortedSet::new(&mut space);
+        set.extend(vec![4, -2, 4, 8, 0, 1]);
+        // The extension stopped at 0, the first new element that did not fit
+        assert_eq!(set.as_slice(), &[-2, 4, 8]);
+        let err = set.insert(0).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NoSpace);
+        assert!(!set.insert(8).unwrap());
+        assert!(set.range(5..).eq(&[8]));
+        assert_eq!(set.range((Excluded(8), Excluded(8))).len(), 0);
+    }
+
+    #[test]
+    fn test_map_entry() {
+        let mut space = alloc_stack_uninit!([(String, u32); 2]);
+        let mut map = FixedSortedMap::from_uninit(&mut space);
+        map.entry(String::from("b")).or_insert(1).unwrap();
+        *map.entry(String::from("b")).or_insert(5).unwrap() += 1;
+        assert_eq!(map["b"], 2);
+
+        let entry = map.entry(String::from("a"));
+        assert!(matches!(entry, Entry::Vacant(_)));
+        assert_eq!(*entry.or_insert(7).unwrap(), 7);
+        let err = map.entry(String::from("c")).or_insert(3).unwrap_err();
+        assert_eq!(err.into_inner(), (String::from("c"), 3));
+
+        if let Entry::Occupied(entry) = map.entry(String::from("a")) {
+            assert_eq!(entry.remove(), 7);
+        }
+        assert!(!map.contains_key("a"));
+        assert!(map.iter().eq(vec![(&String::from("b"), &2)]));
+    }
+
+    #[test]
+    fn test_map_range() {
+        let mut space = alloc_stack!([(u32, u32); 8]);
+        let mut map = FixedSortedMap::new(&mut space);
+        map.extend((0..8).map(|i| (i * 10, i)));
+        assert!(map.range(15..=40).map(|(_, v)| *v).eq(vec![2, 3, 4]));
+        assert!(map.range(..10).rev().map(|(k, _)| *k).eq(vec![0]));
+    }
+}
@@ -0,0 +1,523 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A lock-free single-producer, single-consumer queue.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::{as_uninit_mut, CapacityError, StaticBuffer};
+
+/// A queue for passing elements from one execution context to another, such
+/// as from an interrupt handler to the main loop, without locking.
+///
+/// The queue stores its elements in preallocated memory, and is used through
+/// the `Producer` and `Consumer` halves returned by `split`. Each half can be
+/// moved to a different context. Only atomic loads and stores are used, so
+/// the queue works on targets without compare-and-swap instructions.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::SpscQueue;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 4]);
+/// let mut queue = SpscQueue::new(&mut space);
+/// let (mut producer, mut consumer) = queue.split();
+///
+/// producer.enqueue(1).unwrap();
+/// producer.enqueue(2).unwrap();
+/// assert_eq!(consumer.dequeue(), Some(1));
+/// assert_eq!(consumer.dequeue(), Some(2));
+/// assert_eq!(consumer.dequeue(), None);
+/// # }
+/// ```
+pub struct SpscQueue<'a, T: 'a> {
+    memory: &'a [UnsafeCell<MaybeUninit<T>>],
+    // Both indices count up to twice the capacity before wrapping, so that a
+    // full queue can be told apart from an empty one without wasting a slot.
+    // `head` is only stored to by the consumer, and `tail` by the producer.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+// The elements are moved between contexts, but never shared.
+unsafe impl<'a, T> Send for SpscQueue<'a, T> where T: Send {}
+
+// A shared queue only gives access to its length. The elements are reached
+// through the halves, which are handed out at most once.
+unsafe impl<'a, T> Sync for SpscQueue<'a, T> where T: Send {}
+
+impl<'a, T> SpscQueue<'a, T>
+where
+    T: 'a + Copy,
+{
+    /// Create a new, empty `SpscQueue` from the provided slice, in the
+    /// process taking ownership of the slice.
+    ///
+    /// Only `Copy` types can be managed this way, since the slice still holds
+    /// valid values once the queue is gone. Use `from_uninit` for other types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::SpscQueue;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let queue = SpscQueue::new(&mut space);
+    /// assert_eq!(queue.capacity(), 16);
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [T]) -> Self {
+        SpscQueue::from_uninit(as_uninit_mut(memory))
+    }
+}
+
+impl<'a, T> SpscQueue<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new, empty `SpscQueue` from the provided slice of
+    /// uninitialized memory, in the process taking ownership of the slice.
+    ///
+    /// This works for any element type, including types that are not `Copy`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is greater than `usize::MAX / 2`, which can
+    /// only happen for zero-sized types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::SpscQueue;
+    /// # fn main() {
+    /// let mut space = alloc_stack_uninit!([String; 4]);
+    /// let mut queue = SpscQueue::from_uninit(&mut space);
+    /// let (mut producer, mut consumer) = queue.split();
+    /// producer.enqueue(String::from("hello")).unwrap();
+    /// assert_eq!(consumer.dequeue().unwrap(), "hello");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        assert!(memory.len() <= usize::MAX / 2);
+        // `UnsafeCell<U>` has the same layout as `U`, and the exclusive borrow
+        // guarantees nothing else accesses the memory.
+        let memory =
+            unsafe { &*(memory as *mut [MaybeUninit<T>] as *const [UnsafeCell<MaybeUninit<T>>]) };
+        SpscQueue {
+            memory,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a queue over a `StaticBuffer`, for use by `spsc_static!`.
+    #[doc(hidden)]
+    pub const fn from_static<const N: usize>(buffer: &'a StaticBuffer<T, N>) -> Self {
+        assert!(N <= usize::MAX / 2);
+        // `UnsafeCell<[U; N]>` has the same layout as `[UnsafeCell<U>; N]`.
+        let memory = unsafe {
+            &*(&buffer.memory as *const UnsafeCell<[MaybeUninit<T>; N]>
+                as *const [UnsafeCell<MaybeUninit<T>>; N])
+        };
+        SpscQueue {
+            memory,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Returns the number of elements in the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::SpscQueue;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = SpscQueue::new(&mut space);
+    /// queue.split().0.enqueue(1).unwrap();
+    /// assert_eq!(queue.len(), 1);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.distance(
+            self.head.load(Ordering::Acquire),
+            self.tail.load(Ordering::Acquire),
+        )
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Splits the queue into its producer and consumer halves.
+    ///
+    /// The halves borrow the queue. To get halves that can be handed to an
+    /// interrupt handler, which needs them for `'static`, create the queue
+    /// with `spsc_static!` instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{Consumer, Producer, SpscQueue};
+    /// # fn main() {
+    /// fn isr(producer: &mut Producer<u8>) {
+    ///     let _ = producer.enqueue(42);
+    /// }
+    ///
+    /// fn main_loop(consumer: &mut Consumer<u8>) -> u32 {
+    ///     let mut sum = 0;
+    ///     while let Some(byte) = consumer.dequeue() {
+    ///         sum += u32::from(byte);
+    ///     }
+    ///     sum
+    /// }
+    ///
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut queue = SpscQueue::new(&mut space);
+    /// let (mut producer, mut consumer) = queue.split();
+    /// isr(&mut producer);
+    /// isr(&mut producer);
+    /// assert_eq!(main_loop(&mut consumer), 84);
+    /// # }
+    /// ```
+    pub fn split(&mut self) -> (Producer<'_, 'a, T>, Consumer<'_, 'a, T>) {
+        (Producer { queue: self }, Consumer { queue: self })
+    }
+
+    /// Splits a queue in a `static`, for use by `spsc_static!`.
+    ///
+    /// # Safety
+    ///
+    /// Must be called at most once for each queue.
+    #[doc(hidden)]
+    pub unsafe fn split_static(
+        &'static self,
+    ) -> (Producer<'static, 'a, T>, Consumer<'static, 'a, T>) {
+        (Producer { queue: self }, Consumer { queue: self })
+    }
+
+    /// Returns the number of elements between the indices `head` and `tail`.
+    #[inline]
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + 2 * self.capacity() - head
+        }
+    }
+
+    /// Returns the index following `index`.
+    #[inline]
+    fn next(&self, index: usize) -> usize {
+        if index + 1 == 2 * self.capacity() {
+            0
+        } else {
+            index + 1
+        }
+    }
+
+    /// Returns the slot that `index` refers to.
+    #[inline]
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        let pos = if index >= self.capacity() {
+            index - self.capacity()
+        } else {
+            index
+        };
+        self.memory[pos].get()
+    }
+}
+
+impl<'a, T> Drop for SpscQueue<'a, T> {
+    fn drop(&mut self) {
+        // Both halves are gone, so this is the only context left.
+        while let Some(element) = (Consumer { queue: self }).dequeue() {
+            drop(element);
+        }
+    }
+}
+
+/// The half of an `SpscQueue` that adds elements, created by
+/// `SpscQueue::split`.
+pub struct Producer<'q, 'a: 'q, T: 'a> {
+    queue: &'q SpscQueue<'a, T>,
+}
+
+unsafe impl<'q, 'a, T> Send for Producer<'q, 'a, T> where T: Send {}
+
+impl<'q, 'a, T> Producer<'q, 'a, T> {
+    /// Adds an element to the back of the queue.
+    ///
+    /// If the queue is full, the element is handed back inside the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, SpscQueue};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 1]);
+    /// let mut queue = SpscQueue::new(&mut space);
+    /// let (mut producer, _) = queue.split();
+    /// producer.enqueue(1).unwrap();
+    ///
+    /// let err = producer.enqueue(2).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::NoSpace);
+    /// assert_eq!(err.into_inner(), 2);
+    /// # }
+    /// ```
+    pub fn enqueue(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        let queue = self.queue;
+        let tail = queue.tail.load(Ordering::Relaxed);
+        let head = queue.head.load(Ordering::Acquire);
+        if queue.distance(head, tail) == queue.capacity() {
+            return Err(CapacityError::new(value));
+        }
+        // The slot is outside the range the consumer reads from until `tail`
+        // is published below.
+        unsafe { ptr::write(queue.slot(tail), MaybeUninit::new(value)) };
+        queue.tail.store(queue.next(tail), Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the capacity of the queue.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    /// Returns the number of elements that can be added before the queue is
+    /// full.
+    ///
+    /// The consumer may remove elements at any time, so more space than this
+    /// may be available by the time an element is added.
+    #[inline]
+    pub fn available(&self) -> usize {
+        let queue = self.queue;
+        // Our own index is read first: `head` can only move towards it.
+        let tail = queue.tail.load(Ordering::Relaxed);
+        let head = queue.head.load(Ordering::Acquire);
+        queue.capacity() - queue.distance(head, tail)
+    }
+
+    /// Returns `true` if no more elements can be added to the queue.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.available() == 0
+    }
+}
+
+/// The half of an `SpscQueue` that removes elements, created by
+/// `SpscQueue::split`.
+pub struct Consumer<'q, 'a: 'q, T: 'a> {
+    queue: &'q SpscQueue<'a, T>,
+}
+
+unsafe impl<'q, 'a, T> Send for Consumer<'q, 'a, T> where T: Send {}
+
+impl<'q, 'a, T> Consumer<'q, 'a, T> {
+    /// Removes the element at the front of the queue and returns it, or
+    /// `None` if the queue is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::SpscQueue;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = SpscQueue::new(&mut space);
+    /// let (mut producer, mut consumer) = queue.split();
+    /// assert_eq!(consumer.dequeue(), None);
+    /// producer.enqueue(7).unwrap();
+    /// assert_eq!(consumer.dequeue(), Some(7));
+    /// # }
+    /// ```
+    pub fn dequeue(&mut self) -> Option<T> {
+        let queue = self.queue;
+        let head = queue.head.load(Ordering::Relaxed);
+        let tail = queue.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        // The producer does not touch the slot until `head` moves past it.
+        let value = unsafe { ptr::read((*queue.slot(head)).as_ptr()) };
+        queue.head.store(queue.next(head), Ordering::Release);
+        Some(value)
+    }
+
+    /// Returns a reference to the element at the front of the queue without
+    /// removing it, or `None` if the queue is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::SpscQueue;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = SpscQueue::new(&mut space);
+    /// let (mut producer, mut consumer) = queue.split();
+    /// producer.enqueue(7).unwrap();
+    /// assert_eq!(consumer.peek(), Some(&7));
+    /// assert_eq!(consumer.dequeue(), Some(7));
+    /// # }
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        let queue = self.queue;
+        let head = queue.head.load(Ordering::Relaxed);
+        let tail = queue.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        Some(unsafe { &*(*queue.slot(head)).as_ptr() })
+    }
+
+    /// Returns the capacity of the queue.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    /// Returns the number of elements waiting in the queue.
+    ///
+    /// The producer may add elements at any time, so more elements than this
+    /// may be waiting by the time the next one is removed.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let queue = self.queue;
+        // Our own index is read first: `tail` can only move towards it.
+        let head = queue.head.load(Ordering::Relaxed);
+        let tail = queue.tail.load(Ordering::Acquire);
+        queue.distance(head, tail)
+    }
+
+    /// Returns `true` if no elements are waiting in the queue.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Consumer, Producer, SpscQueue};
+    use std::prelude::v1::*;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn test_fill_and_wrap() {
+        let mut space = alloc_stack!([u8; 3]);
+        let mut queue = SpscQueue::new(&mut space);
+        let (mut producer, mut consumer) = queue.split();
+        for round in 0..10 {
+            for i in 0..3 {
+                producer.enqueue(round + i).unwrap();
+            }
+            assert!(producer.is_full());
+            assert!(producer.enqueue(99).is_err());
+            assert_eq!(consumer.len(), 3);
+            for i in 0..3 {
+                assert_eq!(consumer.dequeue(), Some(round + i));
+            }
+            assert!(consumer.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_drop_remaining() {
+        let counter = Rc::new(());
+        {
+            let mut space = alloc_stack_uninit!([Rc<()>; 4]);
+            let mut queue = SpscQueue::from_uninit(&mut space);
+            let (mut producer, mut consumer) = queue.split();
+            for _ in 0..3 {
+                producer.enqueue(counter.clone()).unwrap();
+            }
+            consumer.dequeue();
+            assert_eq!(Rc::strong_count(&counter), 3);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_threads() {
+        const COUNT: u32 = 100_000;
+        let (mut producer, mut consumer) = spsc_static!([u32; 7]).unwrap();
+
+        let producer = thread::spawn(move || {
+            for i in 0..COUNT {
+                let mut value = i;
+                while let Err(err) = producer.enqueue(value) {
+                    value = err.into_inner();
+                    thread::yield_now();
+                }
+            }
+        });
+        let consumer = thread::spawn(move || {
+            let mut expected = 0;
+            while expected < COUNT {
+                match consumer.dequeue() {
+                    Some(value) => {
+                        assert_eq!(value, expected);
+                        expected += 1;
+                    }
+                    None => thread::yield_now(),
+                }
+            }
+        });
+        producer.join().unwrap();
+        consumer.join().unwrap();
+    }
+
+    #[test]
+    fn test_spsc_static_once() {
+        fn take() -> Option<(
+            Producer<'static, 'static, u8>,
+            Consumer<'static, 'static, u8>,
+        )> {
+            spsc_static!([u8; 2])
+        }
+        assert!(take().is_some());
+        assert!(take().is_none());
+    }
+}
@@ -0,0 +1,453 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! A UTF-8 string with fixed capacity, built on `FixedVec<u8>`.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops;
+use core::str;
+
+use super::{CapacityError, FixedVec, Result};
+
+/// A string that stores its UTF-8 bytes in preallocated memory.
+///
+/// `FixedString` is to `FixedVec<u8>` what `String` is to `Vec<u8>`: the
+/// contents are always valid UTF-8, and it dereferences to `str`, so all of
+/// the string slice methods are available on it.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedString;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 16]);
+/// let mut s = FixedString::new(&mut space);
+///
+/// s.push_str("héllo").unwrap();
+/// s.push('!').unwrap();
+/// assert_eq!(s, "héllo!");
+/// assert_eq!(s.len(), 7);
+/// assert!(s.starts_with("hé"));
+/// # }
+/// ```
+pub struct FixedString<'a> {
+    vec: FixedVec<'a, u8>,
+}
+
+impl<'a> FixedString<'a> {
+    /// Create a new, empty `FixedString` that stores its bytes in `memory`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedString;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let s = FixedString::new(&mut space);
+    /// assert!(s.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn new(memory: &'a mut [u8]) -> Self {
+        FixedString {
+            vec: FixedVec::new(memory),
+        }
+    }
+
+    /// Converts a vector of bytes into a `FixedString`.
+    ///
+    /// If the bytes are not valid UTF-8, the vector is handed back along with
+    /// the reason.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedString, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(b"abc").unwrap();
+    ///
+    /// let s = FixedString::from_utf8(vec).unwrap();
+    /// assert_eq!(s, "abc");
+    ///
+    /// let mut vec = s.into_bytes();
+    /// vec.push(0xff).unwrap();
+    /// let (vec, _err) = FixedString::from_utf8(vec).unwrap_err();
+    /// assert_eq!(vec.as_slice(), b"abc\xff");
+    /// # }
+    /// ```
+    pub fn from_utf8(
+        vec: FixedVec<'a, u8>,
+    ) -> core::result::Result<Self, (FixedVec<'a, u8>, str::Utf8Error)> {
+        match str::from_utf8(vec.as_slice()) {
+            Ok(_) => Ok(FixedString { vec }),
+            Err(err) => Err((vec, err)),
+        }
+    }
+
+    /// Returns the underlying vector of bytes, consuming the string.
+    #[inline]
+    pub fn into_bytes(self) -> FixedVec<'a, u8> {
+        self.vec
+    }
+
+    /// Returns the contents as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // The contents are only ever changed in whole characters
+        unsafe { str::from_utf8_unchecked(self.vec.as_slice()) }
+    }
+
+    /// Returns the contents as a mutable string slice.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(self.vec.as_mut_slice()) }
+    }
+
+    /// Returns the contents as a slice of bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.vec.as_slice()
+    }
+
+    /// Returns the capacity of the string in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the length of the string in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of bytes that can still be added.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.vec.available()
+    }
+
+    /// Returns `true` if the string is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Appends a string slice.
+    ///
+    /// If there is not enough space for all of `s`, nothing is appended.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedString};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut s = FixedString::new(&mut space);
+    /// s.push_str("ab").unwrap();
+    /// assert_eq!(
+    ///     s.push_str("cde"),
+    ///     Err(ErrorKind::CapacityExceeded { requested: 3, available: 2 })
+    /// );
+    /// assert_eq!(s, "ab");
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push_str(&mut self, s: &str) -> Result<()> {
+        self.vec.push_all(s.as_bytes())
+    }
+
+    /// Appends a character.
+    ///
+    /// If there is not enough space for the character's UTF-8 encoding, it is
+    /// handed back inside the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedString;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut s = FixedString::new(&mut space);
+    /// s.push('a').unwrap();
+    /// // 'é' takes two bytes in UTF-8
+    /// assert_eq!(s.push('é').unwrap_err().into_inner(), 'é');
+    /// s.push('b').unwrap();
+    /// assert_eq!(s, "ab");
+    /// # }
+    /// ```
+    pub fn push(&mut self, ch: char) -> core::result::Result<(), CapacityError<char>> {
+        let mut buf = [0; 4];
+        match self.vec.push_all(ch.encode_utf8(&mut buf).as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(CapacityError::new(ch)),
+        }
+    }
+
+    /// Removes the last character and returns it, or `None` if the string is
+    /// empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedString;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut s = FixedString::new(&mut space);
+    /// s.push_str("aé").unwrap();
+    /// assert_eq!(s.pop(), Some('é'));
+    /// assert_eq!(s.pop(), Some('a'));
+    /// assert_eq!(s.pop(), None);
+    /// # }
+    /// ```
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        let new_len = self.len() - ch.len_utf8();
+        self.vec.truncate(new_len);
+        Some(ch)
+    }
+
+    /// Shortens the string to `new_len` bytes.
+    ///
+    /// Has no effect if `new_len` is greater than the current length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a character boundary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedString;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut s = FixedString::new(&mut space);
+    /// s.push_str("héllo").unwrap();
+    /// s.truncate(3);
+    /// assert_eq!(s, "hé");
+    /// # }
+    /// ```
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(
+                self.as_str().is_char_boundary(new_len),
+                "new_len {} does not lie on a char boundary",
+                new_len
+            );
+            self.vec.truncate(new_len);
+        }
+    }
+
+    /// Removes all contents of the string.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+}
+
+impl<'a> ops::Deref for FixedString<'a> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> ops::DerefMut for FixedString<'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<'a> AsRef<str> for FixedString<'a> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> AsRef<[u8]> for FixedString<'a> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<'a> Borrow<str> for FixedString<'a> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> fmt::Display for FixedString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a> fmt::Debug for FixedString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a> fmt::Write for FixedString<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.push(ch).map_err(|_| fmt::Error)
+    }
+}
+
+// Like `FixedVec`, stops at the first character that does not fit.
+impl<'a> Extend<char> for FixedString<'a> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iterable: I) {
+        for ch in iterable {
+            if self.push(ch).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a> From<FixedString<'a>> for FixedVec<'a, u8> {
+    #[inline]
+    fn from(s: FixedString<'a>) -> FixedVec<'a, u8> {
+        s.into_bytes()
+    }
+}
+
+impl<'a, 'b> PartialEq<FixedString<'b>> for FixedString<'a> {
+    #[inline]
+    fn eq(&self, other: &FixedString<'b>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<'a> Eq for FixedString<'a> {}
+
+impl<'a> PartialEq<str> for FixedString<'a> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for FixedString<'a> {
+    #[inline]
+    fn eq(&self, other: &&'b str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<'a> PartialEq<FixedString<'a>> for str {
+    #[inline]
+    fn eq(&self, other: &FixedString<'a>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<'a> PartialEq<FixedString<'a>> for &str {
+    #[inline]
+    fn eq(&self, other: &FixedString<'a>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl<'a, 'b> PartialOrd<FixedString<'b>> for FixedString<'a> {
+    #[inline]
+    fn partial_cmp(&self, other: &FixedString<'b>) -> Option<Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl<'a> Ord for FixedString<'a> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<'a> Hash for FixedString<'a> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedString;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_push_pop_multibyte() {
+        let mut space = alloc_stack!([u8; 8]);
+        let mut s = FixedString::new(&mut space);
+        s.push_str("a€").unwrap();
+        assert_eq!(s.available(), 4);
+        s.push('😀').unwrap();
+        assert_eq!(s.available(), 0);
+        assert!(s.push('b').is_err());
+
+        assert_eq!(s.pop(), Some('😀'));
+        assert_eq!(s.pop(), Some('€'));
+        assert_eq!(s, "a");
+    }
+
+    #[test]
+    #[should_panic(expected = "char boundary")]
+    fn test_truncate_inside_char() {
+        let mut space = alloc_stack!([u8; 8]);
+        let mut s = FixedString::new(&mut space);
+        s.push_str("a€").unwrap();
+        s.truncate(2);
+    }
+
+    #[test]
+    fn test_format() {
+        use core::fmt::Write;
+
+        let mut space = alloc_stack!([u8; 8]);
+        let mut s = FixedString::new(&mut space);
+        write!(s, "{}é", 42).unwrap();
+        assert_eq!(format!("{}", s), "42é");
+        assert_eq!(format!("{:?}", s), "\"42é\"");
+    }
+}